@@ -0,0 +1,197 @@
+//! A fixed-capacity Bip-Buffer backed by an array
+
+use crate::error::Error;
+use crate::regions::Regions;
+
+/// A Bip-Buffer object with its capacity fixed at compile time
+///
+/// Stores its elements inline in a `[T; N]`, so no heap allocation is ever made. Otherwise
+/// behaves like [`BipBuffer`](struct.BipBuffer.html).
+#[derive(Debug)]
+pub struct ArrayBipBuffer<T, const N: usize> {
+    /// Backing store
+    buffer: [T; N],
+    /// Boundaries of the regions within the backing store
+    regions: Regions,
+}
+
+impl<T: Default + Copy, const N: usize> ArrayBipBuffer<T, N> {
+    /// Creates a new buffer of `N` elements
+    pub fn new() -> ArrayBipBuffer<T, N> {
+        ArrayBipBuffer {
+            buffer: [T::default(); N],
+            regions: Regions::default(),
+        }
+    }
+
+    /// Clears all regions and reservations
+    ///
+    /// Data in the underlying buffer is unchanged
+    pub fn clear(&mut self) {
+        self.regions.clear();
+    }
+
+    /// Returns a mutable buffer containing up to `length` slots for storing data.
+    ///
+    /// If there is less free space than requested, the buffer size will equal the free space.
+    /// Returns [`Error`](struct.Error.html) if there is no free space
+    pub fn reserve(&mut self, length: usize) -> Result<&mut [T], Error> {
+        self.regions.reserve(&mut self.buffer, length)
+    }
+
+    /// Commits the data in the reservation, allowing it to be read later
+    ///
+    /// If a `length` of `0` is passed in, the reservation will be cleared without making any
+    /// other changes
+    pub fn commit(&mut self, length: usize) {
+        self.regions.commit(length);
+        self.regions.debug_check_invariants(N);
+    }
+
+    /// Retrieves available (committed) data as a contiguous block.
+    ///
+    /// Returns `None` if there is no data available
+    pub fn read(&mut self) -> Option<&mut [T]> {
+        match self.regions.a().len() {
+            0 => None,
+            _ => Some(&mut self.buffer[self.regions.a()]),
+        }
+    }
+
+    /// Retrieves the committed `A` and `B` regions, in logical order, without mutable access
+    ///
+    /// Either slice is empty if the corresponding region holds no data
+    pub fn committed_slices(&self) -> (&[T], &[T]) {
+        (
+            &self.buffer[self.regions.a()],
+            &self.buffer[self.regions.b()],
+        )
+    }
+
+    /// Marks the first `length` elements of the available data is seen.
+    ///
+    /// The next time `read()` is called, it will not include these elements. If `length` exceeds
+    /// the `A` region, the remainder is taken from the start of the `B` region.
+    pub fn decommit(&mut self, length: usize) {
+        self.regions.decommit(length);
+        self.regions.debug_check_invariants(N);
+    }
+
+    /// Number of committed elements
+    #[inline]
+    pub fn committed_len(&self) -> usize {
+        self.regions.committed_len()
+    }
+
+    /// Number of elements in the largest contiguous block of free space
+    #[inline]
+    pub fn contiguous_free_len(&self) -> usize {
+        self.regions.free_block(N).1
+    }
+
+    /// Number of reserved elements
+    #[inline]
+    pub fn reserved_len(&self) -> usize {
+        self.regions.reserved_len()
+    }
+
+    /// Size of the backing store, which is always `N`
+    #[inline]
+    pub fn len(&self) -> usize {
+        N
+    }
+
+    /// Whether committed data is split across the `A` and `B` regions
+    #[inline]
+    pub fn is_wrapped(&self) -> bool {
+        self.regions.is_wrapped()
+    }
+
+    /// Whether any space has been reserved or committed in the buffer
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.reserved_len() == 0 && self.committed_len() == 0
+    }
+}
+
+impl<T: Default + Copy, const N: usize> Default for ArrayBipBuffer<T, N> {
+    fn default() -> ArrayBipBuffer<T, N> {
+        ArrayBipBuffer::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_empty() {
+        let mut buffer: ArrayBipBuffer<u8, 3> = ArrayBipBuffer::new();
+        assert_eq!(buffer.read(), None);
+        assert_eq!(buffer.len(), 3);
+    }
+    #[test]
+    fn read_uncommitted() {
+        let mut buffer: ArrayBipBuffer<u8, 3> = ArrayBipBuffer::new();
+        buffer.reserve(2).unwrap();
+        assert_eq!(buffer.read(), None);
+    }
+    #[test]
+    fn reserve_gt_overall_len() {
+        let mut buffer: ArrayBipBuffer<u8, 3> = ArrayBipBuffer::new();
+        assert_eq!(buffer.reserve(4).unwrap().len(), 3);
+        assert_eq!(buffer.reserved_len(), 3);
+    }
+    #[test]
+    fn commit_and_fetch() {
+        let mut buffer: ArrayBipBuffer<u8, 4> = ArrayBipBuffer::new();
+        {
+            let reserved = buffer.reserve(3).unwrap();
+            reserved.copy_from_slice(&[7, 22, 218]);
+        }
+        assert_eq!(buffer.committed_len(), 0);
+        buffer.commit(3);
+        assert_eq!(buffer.committed_len(), 3);
+        assert_eq!(buffer.reserved_len(), 0);
+        assert_eq!(buffer.read().unwrap(), &[7, 22, 218]);
+    }
+    #[test]
+    fn reserve_full() {
+        let mut buffer: ArrayBipBuffer<u8, 4> = ArrayBipBuffer::new();
+        buffer.reserve(4).unwrap();
+        buffer.commit(4);
+        assert!(buffer.reserve(1).is_err());
+    }
+    #[test]
+    fn reserve_after_full_cycle() {
+        let mut buffer: ArrayBipBuffer<u8, 4> = ArrayBipBuffer::new();
+        {
+            let reserved = buffer.reserve(4).unwrap();
+            reserved.copy_from_slice(&[7, 22, 218, 56]);
+        }
+        buffer.commit(4);
+        buffer.decommit(2);
+        {
+            let reserved = buffer.reserve(4).unwrap();
+            assert_eq!(reserved.len(), 2);
+            reserved.copy_from_slice(&[49, 81]);
+        }
+        buffer.commit(2);
+        assert!(buffer.is_wrapped());
+        assert_eq!(buffer.committed_slices(), (&[218, 56][..], &[49, 81][..]));
+        assert_eq!(buffer.read().unwrap(), &[218, 56]);
+        buffer.decommit(2);
+        assert_eq!(buffer.read().unwrap(), &[49, 81]);
+        buffer.decommit(2);
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.contiguous_free_len(), 4);
+    }
+    #[test]
+    fn clear() {
+        let mut buffer: ArrayBipBuffer<u8, 4> = ArrayBipBuffer::default();
+        buffer.reserve(4).unwrap();
+        buffer.commit(4);
+        buffer.clear();
+        assert_eq!(buffer.committed_len(), 0);
+    }
+}
@@ -0,0 +1,125 @@
+//! A builder for configuring a Bip-Buffer at construction time
+
+use crate::BipBuffer;
+
+/// Configures and creates a `BipBuffer`
+///
+/// With only a capacity set, `build()` is equivalent to
+/// [`BipBuffer::new()`](struct.BipBuffer.html#method.new).
+///
+/// # Examples
+/// ```rust
+/// use bipbuffer::BipBufferBuilder;
+///
+/// let buffer = BipBufferBuilder::new()
+///     .capacity(64)
+///     .fill(0xFFu8)
+///     .high_watermark(48)
+///     .build();
+/// assert_eq!(buffer.len(), 64);
+/// ```
+#[derive(Debug)]
+pub struct BipBufferBuilder<T> {
+    capacity: usize,
+    fill: Option<T>,
+    auto_grow: bool,
+    high_watermark: usize,
+}
+
+impl<T> BipBufferBuilder<T> {
+    /// Creates a builder for an empty, zero-length buffer
+    pub fn new() -> BipBufferBuilder<T> {
+        BipBufferBuilder {
+            capacity: 0,
+            fill: None,
+            auto_grow: false,
+            high_watermark: 0,
+        }
+    }
+
+    /// Sets the number of elements in the backing store
+    pub fn capacity(mut self, capacity: usize) -> BipBufferBuilder<T> {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Sets the value every slot is initialized to, instead of `T::default()`
+    ///
+    /// Slots added later by auto-grow still hold `T::default()`
+    pub fn fill(mut self, value: T) -> BipBufferBuilder<T> {
+        self.fill = Some(value);
+        self
+    }
+
+    /// Sets whether `reserve()` grows the backing store; see
+    /// [`BipBuffer::set_auto_grow()`](struct.BipBuffer.html#method.set_auto_grow)
+    pub fn auto_grow(mut self, enabled: bool) -> BipBufferBuilder<T> {
+        self.auto_grow = enabled;
+        self
+    }
+
+    /// Sets the high watermark; see
+    /// [`BipBuffer::set_high_watermark()`](struct.BipBuffer.html#method.set_high_watermark)
+    pub fn high_watermark(mut self, threshold: usize) -> BipBufferBuilder<T> {
+        self.high_watermark = threshold;
+        self
+    }
+}
+
+impl<T: Default + Clone> BipBufferBuilder<T> {
+    /// Creates and allocates the configured buffer
+    ///
+    /// Requires `T: Default` because slots are filled with `T::default()` when no
+    /// [`fill()`](#method.fill) value is set, and because most buffer operations need it
+    pub fn build(self) -> BipBuffer<T> {
+        let mut buffer = match self.fill {
+            Some(value) => BipBuffer::with_default(self.capacity, value),
+            None => BipBuffer::new(self.capacity),
+        };
+        buffer.set_auto_grow(self.auto_grow);
+        buffer.set_high_watermark(self.high_watermark);
+        buffer
+    }
+}
+
+impl<T> Default for BipBufferBuilder<T> {
+    fn default() -> BipBufferBuilder<T> {
+        BipBufferBuilder::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capacity_only() {
+        let mut buffer: BipBuffer<u8> = BipBufferBuilder::new().capacity(4).build();
+        assert_eq!(buffer.len(), 4);
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.reserve(8).unwrap(), &[0; 4]);
+        buffer.commit(4);
+        assert!(!buffer.over_high_watermark());
+        assert!(buffer.reserve(1).is_err());
+    }
+    #[test]
+    fn fill_and_auto_grow() {
+        let mut buffer = BipBufferBuilder::new()
+            .capacity(4)
+            .fill(0xAAu8)
+            .auto_grow(true)
+            .high_watermark(6)
+            .build();
+        assert_eq!(buffer.reserve(2).unwrap(), &[0xAA; 2]);
+        buffer.commit(2);
+        {
+            let reserved = buffer.reserve(6).unwrap();
+            assert_eq!(reserved.len(), 6);
+            assert_eq!(reserved[..2], [0xAA; 2]);
+            assert_eq!(reserved[2..], [0; 4]);
+        }
+        buffer.commit(6);
+        assert_eq!(buffer.len(), 8);
+        assert!(buffer.over_high_watermark());
+    }
+}
@@ -16,12 +16,27 @@ pub enum ErrorKind {
     /// No space is available for writing to the buffer; data must be marked read by calling
     /// [`decommit()`](struct.BipBuffer.html#method.decommit)
     NoSpace,
+    /// Data was committed without first calling
+    /// [`reserve()`](struct.BipBuffer.html#method.reserve)
+    NoReservation,
+    /// A [`Checkpoint`](struct.Checkpoint.html) could not be restored because the buffer was
+    /// changed in a way that may have overwritten the data it captured
+    StaleCheckpoint,
+}
+
+impl Error {
+    /// Returns the specific type of this error
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
 }
 
 impl ErrorKind {
     fn as_str(self) -> &'static str {
         match self {
             ErrorKind::NoSpace => "no space",
+            ErrorKind::NoReservation => "no reservation",
+            ErrorKind::StaleCheckpoint => "stale checkpoint",
         }
     }
 }
@@ -53,28 +53,131 @@
 //! }
 //! ```
 //! [1]: https://www.codeproject.com/articles/3479/the-bip-buffer-the-circular-buffer-with-a-twist
+mod array;
+mod builder;
 mod error;
+mod regions;
+mod sync;
 
+pub use crate::array::ArrayBipBuffer;
+pub use crate::builder::BipBufferBuilder;
 pub use crate::error::{Error, ErrorKind};
+use crate::regions::Regions;
+pub use crate::sync::SyncBipBuffer;
+use std::convert::TryFrom;
 use std::default::Default;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::io::{self, Read, Write};
+use std::iter::Chain;
+use std::ops::Range;
+use std::{slice, vec};
+
+/// Size of the length prefix written by `push_framed()`
+const FRAME_PREFIX_LEN: usize = 4;
 
 /// A Bip-Buffer object
-#[derive(Debug)]
+///
+/// The `Debug` output shows the committed data in logical order rather than the whole backing
+/// store; the alternate form (`{:#?}`) also includes the raw region indices.
 pub struct BipBuffer<T> {
     /// Backing store
     buffer: Vec<T>,
-    /// Index of the start of the `A` region
-    a_start: usize,
-    /// Index of the end of the `A` region
-    a_end: usize,
-    /// Index of the start of the `B` region
-    b_start: usize,
-    /// Index of the end of the `B` region
-    b_end: usize,
-    /// Index of the start of the reserved region
-    reserve_start: usize,
-    /// Index of the end of the reserved region
-    reserve_end: usize,
+    /// Boundaries of the regions within the backing store
+    regions: Regions,
+    /// Committed length at which the buffer reports being over its high watermark
+    high_watermark: usize,
+    /// Whether `reserve()` grows the backing store instead of running out of space
+    auto_grow: bool,
+    /// Cumulative operation counters
+    stats: BipBufferStats,
+    /// Number of operations that have changed the committed regions or their contents
+    generation: u64,
+    /// Number of operations, other than decommits, that may have overwritten decommitted slots
+    overwrites: u64,
+    /// Whether `reserve()` panics if the reservation overlaps committed data
+    debug_overlap_checks: bool,
+    /// Committed length above which `commit()` drops the oldest data, or `0` for no limit
+    max_committed: usize,
+    /// Number of elements discarded by lossy operations rather than read
+    dropped: u64,
+}
+
+/// Saved region boundaries of a `BipBuffer`, for re-reading decommitted data
+///
+/// Created by [`checkpoint()`](struct.BipBuffer.html#method.checkpoint) and consumed by
+/// [`restore()`](struct.BipBuffer.html#method.restore)
+#[derive(Clone, Copy, Debug)]
+pub struct Checkpoint {
+    /// Region boundaries when the checkpoint was taken
+    regions: Regions,
+    /// Overwrite count when the checkpoint was taken
+    overwrites: u64,
+}
+
+/// Cumulative counters describing how a `BipBuffer` has been used
+///
+/// Retrieved with [`stats()`](struct.BipBuffer.html#method.stats)
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct BipBufferStats {
+    /// Total number of elements committed
+    pub committed: u64,
+    /// Total number of elements decommitted
+    pub decommitted: u64,
+    /// Number of times `reserve()` returned a `NoSpace` error
+    pub no_space_errors: u64,
+    /// Number of times a commit started a new `B` region, wrapping the buffer
+    pub wraps: u64,
+}
+
+impl<T> BipBuffer<T> {
+    /// Wraps an initialized backing store, with all regions empty
+    fn from_backing(buffer: Vec<T>) -> BipBuffer<T> {
+        BipBuffer {
+            buffer,
+            regions: Regions::default(),
+            high_watermark: 0,
+            auto_grow: false,
+            stats: BipBufferStats::default(),
+            generation: 0,
+            overwrites: 0,
+            debug_overlap_checks: false,
+            max_committed: 0,
+            dropped: 0,
+        }
+    }
+
+    /// Creates a buffer from a circular buffer stored in `data`, whose `len` elements start at
+    /// index `head` and wrap around to the start of `data`
+    ///
+    /// `data` becomes the backing store, rearranged so the elements are committed in the same
+    /// logical order as a single `A` region at its start. `head` is ignored if `len` is `0`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` exceeds `data.len()`, or if the ring is not empty and `head` is out of
+    /// bounds for `data`
+    pub fn from_ring(mut data: Vec<T>, head: usize, len: usize) -> BipBuffer<T> {
+        assert!(len <= data.len(), "ring length exceeds its storage");
+        if len > 0 {
+            assert!(head < data.len(), "ring head is out of bounds");
+            data.rotate_left(head);
+        }
+        let mut buffer = BipBuffer::from_backing(data);
+        buffer.regions.a_end = len;
+        buffer.regions.debug_check_invariants(buffer.buffer.len());
+        buffer
+    }
+}
+
+impl<T: Clone> BipBuffer<T> {
+    /// Creates and allocates a new buffer of `T` elements, each initialized to `value`
+    ///
+    /// Useful for filling unused slots with a recognizable sentinel while debugging. Unlike
+    /// [`new()`](#method.new), this doesn't require `T: Default`.
+    pub fn with_default(length: usize, value: T) -> BipBuffer<T> {
+        BipBuffer::from_backing(vec![value; length])
+    }
 }
 
 impl<T: Default> BipBuffer<T> {
@@ -84,55 +187,227 @@ impl<T: Default> BipBuffer<T> {
         for _ in 0..length {
             buffer.push(Default::default());
         }
-        BipBuffer {
-            buffer,
-            a_start: 0,
-            a_end: 0,
-            b_start: 0,
-            b_end: 0,
-            reserve_start: 0,
-            reserve_end: 0,
-        }
+        BipBuffer::from_backing(buffer)
     }
 
     /// Clears all regions and reservations
     ///
     /// Data in the underlying buffer is unchanged
     pub fn clear(&mut self) {
-        self.a_start = 0;
-        self.a_end = 0;
-        self.b_start = 0;
-        self.b_end = 0;
-        self.reserve_start = 0;
-        self.reserve_end = 0;
+        let before = self.committed_bounds();
+        self.regions.clear();
+        self.track_generation(before);
+    }
+
+    /// Discards all data and resizes the backing store to `new_length` elements
+    ///
+    /// Every slot is reset to `T::default()`. The existing allocation is reused if
+    /// `new_length <= capacity()`.
+    pub fn reset(&mut self, new_length: usize) {
+        self.buffer.clear();
+        self.buffer.resize_with(new_length, Default::default);
+        self.regions.clear();
+        self.advance_generation(true);
     }
 
     /// Returns a mutable buffer containing up to `length` slots for storing data.
     ///
-    /// If there is less free space than requested, the buffer size will equal the free space.
+    /// If there is less free space than requested, the buffer size will equal the free space,
+    /// unless auto-grow is enabled, in which case the backing store grows to fit `length`.
     /// Returns [`Error`](struct.Error.html) if there is no free space
     pub fn reserve(&mut self, length: usize) -> Result<&mut [T], Error> {
-        let reserve_start;
-        let free_space = if (self.b_end - self.b_start) > 0 {
-            reserve_start = self.b_end;
-            self.a_start - self.b_end
-        } else {
-            let space_after_a = self.len() - self.a_end;
-            if space_after_a >= self.a_start {
-                reserve_start = self.a_end;
-                space_after_a
-            } else {
-                reserve_start = 0;
-                self.a_start
+        self.grow_for(length);
+        let result = self.regions.reserve(&mut self.buffer, length).map(|_| ());
+        self.finish_reservation(result)
+    }
+
+    /// Records the outcome of a reservation attempt, returning the reserved slots on success
+    fn finish_reservation(&mut self, result: Result<(), Error>) -> Result<&mut [T], Error> {
+        match result {
+            Ok(()) => {
+                self.overwrites += 1;
+                if self.debug_overlap_checks {
+                    self.regions.assert_reservation_disjoint();
+                }
+                Ok(&mut self.buffer[self.regions.reserved()])
             }
-        };
-        if free_space == 0 {
+            Err(err) => {
+                self.stats.no_space_errors += 1;
+                Err(err)
+            }
+        }
+    }
+
+    /// Returns a mutable buffer containing up to `length` slots for storing data, each reset to
+    /// `T::default()`
+    ///
+    /// Behaves like [`reserve()`](#method.reserve), but guarantees no stale data from earlier
+    /// commits is left in the returned slots
+    pub fn reserve_zeroed(&mut self, length: usize) -> Result<&mut [T], Error>
+    where
+        T: Clone,
+    {
+        let reserved = self.reserve(length)?;
+        reserved.fill(T::default());
+        Ok(reserved)
+    }
+
+    /// Returns a mutable buffer containing all of the largest contiguous block of free space
+    ///
+    /// Equivalent to `reserve(contiguous_free_len())`.
+    /// Returns [`Error`](struct.Error.html) if there is no free space
+    pub fn reserve_all(&mut self) -> Result<&mut [T], Error> {
+        let length = self.contiguous_free_len();
+        self.reserve(length)
+    }
+
+    /// Returns a mutable buffer containing at least `min` and up to `max` slots for storing data.
+    ///
+    /// Returns [`Error`](struct.Error.html), leaving any existing reservation in place, if
+    /// `min > max` or the largest contiguous block of free space is smaller than `min`
+    pub fn reserve_between(&mut self, min: usize, max: usize) -> Result<&mut [T], Error> {
+        if min > max {
             return Err(ErrorKind::NoSpace.into());
         }
-        let reserve_length = std::cmp::min(free_space, length);
-        self.reserve_start = reserve_start;
-        self.reserve_end = reserve_start + reserve_length;
-        Ok(&mut self.buffer[self.reserve_start..self.reserve_end])
+        self.grow_for(min);
+        if self.contiguous_free_len() < min {
+            self.stats.no_space_errors += 1;
+            return Err(ErrorKind::NoSpace.into());
+        }
+        self.reserve(max)
+    }
+
+    /// Returns a mutable buffer of exactly `length` slots, starting at an index in the backing
+    /// store that is a multiple of `align`
+    ///
+    /// To stay contiguous with the committed data, the reservation either directly follows it
+    /// (if that position is aligned) or starts at the beginning of the backing store, skipping
+    /// any space left at the end. Skipped slots are never committed. Otherwise behaves like
+    /// [`reserve()`](#method.reserve), including auto-grow and overlap checks.
+    ///
+    /// Since a region can't contain gaps, an aligned start past the end of unaligned committed
+    /// data is never used: with `A` at `0..5` in a 16-slot buffer, `reserve_aligned(4, 4)` fails
+    /// even though slots `8..12` are free.
+    /// Returns [`Error`](struct.Error.html) if no aligned block of `length` slots is free
+    ///
+    /// # Panics
+    ///
+    /// Panics if `align` is zero
+    pub fn reserve_aligned(&mut self, length: usize, align: usize) -> Result<&mut [T], Error> {
+        self.grow_for(length);
+        let result = self
+            .regions
+            .reserve_aligned(&mut self.buffer, length, align)
+            .map(|_| ());
+        self.finish_reservation(result)
+    }
+
+    /// Returns a mutable buffer of exactly `length` slots from the free space before the `A`
+    /// region, even if more space is available after it
+    ///
+    /// Committing the reservation adds to the `B` region, wrapping the buffer.
+    /// Returns [`Error`](struct.Error.html) if nothing is committed, or if the space before the
+    /// `A` region can't hold `length` slots
+    pub fn reserve_in_b(&mut self, length: usize) -> Result<&mut [T], Error> {
+        let result = self.regions.reserve_in_b(&mut self.buffer, length);
+        match result {
+            Ok(_) => self.overwrites += 1,
+            Err(_) => self.stats.no_space_errors += 1,
+        }
+        result
+    }
+
+    /// Returns a mutable buffer containing up to `length` slots for storing data.
+    ///
+    /// Behaves like [`reserve()`](#method.reserve), except that an empty slice is returned
+    /// (and the reservation cleared) if there is no free space
+    pub fn reserve_up_to(&mut self, length: usize) -> &mut [T] {
+        self.grow_for(length);
+        let reserved = self.regions.reserve_up_to(&mut self.buffer, length);
+        if !reserved.is_empty() {
+            self.overwrites += 1;
+        }
+        reserved
+    }
+
+    /// Returns up to two mutable buffers containing up to `length` slots in total for storing
+    /// data
+    ///
+    /// When the free space is split between the end of the backing store and the start, the
+    /// first buffer covers the end and the second covers the start; otherwise the second buffer
+    /// is empty. Committing fills the first buffer before the second. Both buffers are empty if
+    /// there is no free space.
+    pub fn reserve_vectored(&mut self, length: usize) -> (&mut [T], &mut [T]) {
+        self.grow_for(length);
+        let (first, second) = self.regions.reserve_vectored(&mut self.buffer, length);
+        if !first.is_empty() || !second.is_empty() {
+            self.overwrites += 1;
+        }
+        (first, second)
+    }
+
+    /// Retrieves the slots handed out by the last call to `reserve()`
+    ///
+    /// Returns `None` if there is no active reservation
+    pub fn reserved(&mut self) -> Option<&mut [T]> {
+        match self.regions.reserved_len() {
+            0 => None,
+            _ => Some(&mut self.buffer[self.regions.reserved()]),
+        }
+    }
+
+    /// Sets whether `reserve()` grows the backing store when there isn't enough contiguous free
+    /// space for the requested length
+    ///
+    /// When enabled, the backing store doubles in size until the request fits in full, and
+    /// committed data is moved to the front. Disabled by default.
+    pub fn set_auto_grow(&mut self, enabled: bool) {
+        self.auto_grow = enabled;
+    }
+
+    /// Limits the amount of committed data kept, or removes the limit if `limit` is `0`
+    ///
+    /// Whenever a commit leaves more than `limit` elements committed, the oldest are decommitted
+    /// until exactly `limit` remain. Data already committed is left alone until the next commit.
+    pub fn set_max_committed(&mut self, limit: usize) {
+        self.max_committed = limit;
+    }
+
+    /// Enables or disables checking that every reservation made by
+    /// [`reserve()`](#method.reserve) is disjoint from the committed `A` and `B` regions
+    ///
+    /// When enabled, `reserve()` panics if the check fails. This is a development aid for
+    /// catching region bookkeeping bugs early. Disabled by default.
+    pub fn set_debug_overlap_checks(&mut self, enabled: bool) {
+        self.debug_overlap_checks = enabled;
+    }
+
+    /// Grows the backing store so that `length` contiguous slots are free, if auto-grow is
+    /// enabled and the space isn't already available
+    ///
+    /// Requests too large to allocate are left to be clamped as usual
+    fn grow_for(&mut self, length: usize) {
+        if !self.auto_grow || self.contiguous_free_len() >= length {
+            return;
+        }
+        let required = match self.committed_len().checked_add(length) {
+            Some(required) => required,
+            None => return,
+        };
+        let mut new_length = std::cmp::max(self.len(), 1);
+        while new_length < required {
+            new_length = new_length.checked_mul(2).unwrap_or(required);
+        }
+        if self
+            .buffer
+            .try_reserve_exact(new_length - self.len())
+            .is_err()
+        {
+            return;
+        }
+        self.compact();
+        self.buffer.resize_with(new_length, Default::default);
+        self.debug_check_invariants();
     }
 
     /// Commits the data in the reservation, allowing it to be read later
@@ -140,96 +415,1060 @@ impl<T: Default> BipBuffer<T> {
     /// If a `length` of `0` is passed in, the reservation will be cleared without making any
     /// other changes
     pub fn commit(&mut self, length: usize) {
-        if length == 0 {
-            self.reserve_start = 0;
-            self.reserve_end = 0;
-            return;
+        let committed_before = self.committed_len();
+        let wrapped_before = self.is_wrapped();
+        self.regions.commit(length);
+        if self.committed_len() != committed_before {
+            self.advance_generation(true);
+        }
+        self.stats.committed += (self.committed_len() - committed_before) as u64;
+        if !wrapped_before && self.is_wrapped() {
+            self.stats.wraps += 1;
+        }
+        if self.max_committed > 0 && self.committed_len() > self.max_committed {
+            self.drop_oldest(self.committed_len() - self.max_committed);
+        }
+        self.debug_check_invariants();
+    }
+
+    /// Like [`commit()`](#method.commit), but checks that a reservation is active
+    ///
+    /// Returns [`Error`](struct.Error.html) without changing the buffer if there is no active
+    /// reservation and `length` is nonzero
+    pub fn try_commit(&mut self, length: usize) -> Result<(), Error> {
+        if length > 0 && self.reserved_len() == 0 {
+            return Err(ErrorKind::NoReservation.into());
         }
-        let to_commit = std::cmp::min(length, self.reserve_end - self.reserve_start);
-        if self.a_end - self.a_start == 0 && self.b_end - self.b_start == 0 {
-            self.a_start = self.reserve_start;
-            self.a_end = self.reserve_start + to_commit;
-        } else if self.reserve_start == self.a_end {
-            self.a_end += to_commit;
+        self.commit(length);
+        Ok(())
+    }
+
+    /// Commits the first `length` elements of the reservation, keeping the rest reserved
+    ///
+    /// Returns the remaining reserved slots, which directly follow the newly committed data and
+    /// are empty if the whole reservation was committed. The wrapped part of a vectored
+    /// reservation is discarded.
+    /// Returns [`Error`](struct.Error.html) if there is no active reservation
+    pub fn commit_and_keep(&mut self, length: usize) -> Result<&mut [T], Error> {
+        if self.reserved_len() == 0 {
+            return Err(ErrorKind::NoReservation.into());
+        }
+        let (reserve_start, reserve_end) = self.reserved_region();
+        let to_commit = std::cmp::min(length, reserve_end - reserve_start);
+        self.commit(to_commit);
+        if reserve_start + to_commit < reserve_end {
+            self.regions.reserve_start = reserve_start + to_commit;
+            self.regions.reserve_end = reserve_end;
+        }
+        self.debug_check_invariants();
+        Ok(&mut self.buffer[self.regions.reserved()])
+    }
+
+    /// Reserves up to `length` slots, passes them to `f`, then commits the number of elements `f`
+    /// reports having written
+    ///
+    /// The count returned by `f` is clamped to the size of the reservation; any extra value it
+    /// returns is passed back to the caller.
+    /// Returns [`Error`](struct.Error.html) without calling `f` if there is no free space
+    pub fn reserve_with<F, R>(&mut self, length: usize, f: F) -> Result<R, Error>
+    where
+        F: FnOnce(&mut [T]) -> (usize, R),
+    {
+        let (written, result) = f(self.reserve(length)?);
+        self.commit(written);
+        Ok(result)
+    }
+
+    /// Reserves the largest contiguous block of free space, passes it to `f`, then commits the
+    /// number of elements `f` reports having written
+    ///
+    /// The count returned by `f` is clamped to the size of the reservation. Returns the number
+    /// of elements committed, which is `0` without calling `f` if the buffer is full.
+    pub fn fill_from<F: FnOnce(&mut [T]) -> usize>(&mut self, f: F) -> usize {
+        let reserved = match self.reserve_all() {
+            Ok(reserved) => reserved,
+            Err(_) => return 0,
+        };
+        let length = reserved.len();
+        let written = std::cmp::min(f(reserved), length);
+        self.commit(written);
+        written
+    }
+
+    /// Copies `data` into the buffer and commits it, dropping the oldest committed elements as
+    /// needed to make room
+    ///
+    /// If `data` is longer than the buffer, only its last `len()` elements are kept, unless
+    /// auto-grow is enabled. Returns the number of elements stored.
+    pub fn push_slice_lossy(&mut self, data: &[T]) -> usize
+    where
+        T: Copy,
+    {
+        let data = if !self.auto_grow && data.len() > self.len() {
+            self.dropped += (data.len() - self.len()) as u64;
+            &data[data.len() - self.len()..]
         } else {
-            self.b_end += to_commit;
+            data
+        };
+        let mut pushed = 0;
+        while pushed < data.len() {
+            let remaining = data.len() - pushed;
+            let reserved = self.reserve_up_to(remaining);
+            if reserved.is_empty() {
+                self.drop_oldest(remaining.saturating_sub(self.free_len()));
+                if self.contiguous_free_len() < remaining {
+                    self.compact();
+                }
+                continue;
+            }
+            let count = reserved.len();
+            reserved.copy_from_slice(&data[pushed..pushed + count]);
+            self.commit(count);
+            pushed += count;
+        }
+        pushed
+    }
+
+    /// Moves as much of `other`'s committed data as fits into this buffer, in logical order
+    ///
+    /// The moved elements are committed after this buffer's existing data and decommitted from
+    /// `other`; anything that doesn't fit stays committed in `other`. Returns the number of
+    /// elements moved.
+    pub fn append(&mut self, other: &mut BipBuffer<T>) -> usize
+    where
+        T: Copy,
+    {
+        let mut moved = 0;
+        while let Some(block) = other.read() {
+            let reserved = self.reserve_up_to(block.len());
+            if reserved.is_empty() {
+                break;
+            }
+            let count = reserved.len();
+            reserved.copy_from_slice(&block[..count]);
+            self.commit(count);
+            other.decommit(count);
+            moved += count;
+        }
+        moved
+    }
+
+    /// Replaces all committed data with `data`, returning the old contents in logical order
+    ///
+    /// Only as much of `data` as fits (starting from its beginning) is stored
+    pub fn replace_with(&mut self, data: &[T]) -> Vec<T>
+    where
+        T: Clone,
+    {
+        let old = self.to_vec();
+        self.clear();
+        if let Ok(reserved) = self.reserve(data.len()) {
+            let count = reserved.len();
+            reserved.clone_from_slice(&data[..count]);
+            self.commit(count);
         }
-        self.reserve_start = 0;
-        self.reserve_end = 0;
+        old
     }
 
     /// Retrieves available (committed) data as a contiguous block.
     ///
     /// Returns `None` if there is no data available
     pub fn read(&mut self) -> Option<&mut [T]> {
-        match self.a_end - self.a_start {
+        match self.regions.a().len() {
             0 => None,
-            _ => Some(&mut self.buffer[self.a_start..self.a_end]),
+            _ => Some(&mut self.buffer[self.regions.a()]),
+        }
+    }
+
+    /// Retrieves all committed data as a single contiguous block
+    ///
+    /// If the data is wrapped, it is first compacted into the `A` region, which clears any
+    /// reservation; otherwise this is equivalent to `read()`.
+    /// Returns `None` if there is no data available
+    pub fn read_contiguous(&mut self) -> Option<&mut [T]> {
+        if self.is_wrapped() {
+            self.compact();
+        }
+        self.read()
+    }
+
+    /// Moves all committed data, in logical order, to a single `A` region at the start of the
+    /// backing store
+    ///
+    /// This frees up the largest possible contiguous block for reserving. Any reservation is
+    /// cleared.
+    pub fn compact(&mut self) {
+        let before = self.committed_bounds();
+        self.regions.compact(&mut self.buffer);
+        self.track_generation(before);
+        self.debug_check_invariants();
+    }
+
+    /// Retrieves the committed `A` and `B` regions, in logical order, without mutable access
+    ///
+    /// Either slice is empty if the corresponding region holds no data
+    pub fn committed_slices(&self) -> (&[T], &[T]) {
+        (
+            &self.buffer[self.regions.a()],
+            &self.buffer[self.regions.b()],
+        )
+    }
+
+    /// Copies all committed data, in logical order, into a new `Vec`
+    pub fn to_vec(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        let (a, b) = self.committed_slices();
+        let mut data = Vec::with_capacity(a.len() + b.len());
+        data.extend_from_slice(a);
+        data.extend_from_slice(b);
+        data
+    }
+
+    /// Retrieves the oldest committed element
+    ///
+    /// This is the first element of the `A` region, which the next `decommit(1)` will consume.
+    /// Returns `None` if there is no data available
+    pub fn peek_front(&self) -> Option<&T> {
+        self.buffer[self.regions.a()].first()
+    }
+
+    /// Retrieves the most recently committed element
+    ///
+    /// This is the last element of the `B` region if the buffer is wrapped, otherwise the last
+    /// element of the `A` region. Returns `None` if there is no data available
+    pub fn peek_back(&self) -> Option<&T> {
+        let (a, b) = self.committed_slices();
+        b.last().or_else(|| a.last())
+    }
+
+    /// Copies the first `n` committed elements, in logical order, into a new `Vec`
+    ///
+    /// Returns `None` if fewer than `n` elements are committed
+    pub fn peek_n(&self, n: usize) -> Option<Vec<T>>
+    where
+        T: Clone,
+    {
+        if self.committed_len() < n {
+            return None;
+        }
+        let (a, b) = self.committed_slices();
+        let from_a = std::cmp::min(a.len(), n);
+        let mut data = Vec::with_capacity(n);
+        data.extend_from_slice(&a[..from_a]);
+        data.extend_from_slice(&b[..n - from_a]);
+        Some(data)
+    }
+
+    /// Returns the first `n` committed elements for in-place editing, without decommitting them
+    ///
+    /// Returns `None` if the `A` region holds fewer than `n` elements, either because not enough
+    /// data is committed or because it wraps into the `B` region
+    pub fn peek_mut_n(&mut self, n: usize) -> Option<&mut [T]> {
+        let a = self.regions.a();
+        if a.len() < n {
+            return None;
+        }
+        Some(&mut self.buffer[a.start..a.start + n])
+    }
+
+    /// Returns the first `n` committed elements as one contiguous slice, compacting the buffer
+    /// if they are split between the `A` and `B` regions
+    ///
+    /// No data is moved if the `A` region already holds `n` elements. Compacting clears any
+    /// reservation. Returns `None` if fewer than `n` elements are committed.
+    pub fn ensure_contiguous(&mut self, n: usize) -> Option<&mut [T]> {
+        if self.committed_len() < n {
+            return None;
         }
+        if self.regions.a().len() < n {
+            self.compact();
+        }
+        self.peek_mut_n(n)
     }
 
     /// Marks the first `length` elements of the available data is seen.
     ///
-    /// The next time `read()` is called, it will not include these elements.
+    /// The next time `read()` is called, it will not include these elements. If `length` exceeds
+    /// the `A` region, the remainder is taken from the start of the `B` region; if it exceeds
+    /// `committed_len()`, the buffer is left empty.
     pub fn decommit(&mut self, length: usize) {
-        if length >= self.a_end - self.a_start {
-            self.a_start = self.b_start;
-            self.a_end = self.b_end;
-            self.b_start = 0;
-            self.b_end = 0;
+        let committed_before = self.committed_len();
+        self.regions.decommit(length);
+        if self.committed_len() != committed_before {
+            self.advance_generation(false);
+        }
+        self.stats.decommitted += (committed_before - self.committed_len()) as u64;
+        self.debug_check_invariants();
+    }
+
+    /// Decommits up to `consume` elements, but never so many that fewer than `keep` remain
+    ///
+    /// Useful for keeping a window of recent history available. If `committed_len()` is already
+    /// at most `keep`, nothing is decommitted.
+    pub fn decommit_keeping(&mut self, consume: usize, keep: usize) {
+        let limit = self.committed_len().saturating_sub(keep);
+        self.decommit(std::cmp::min(consume, limit));
+    }
+
+    /// Passes the contiguous readable block (the `A` region) to `f`, then decommits the number of
+    /// elements `f` reports having consumed
+    ///
+    /// The count returned by `f` is clamped to the length of the block. `f` is called with an
+    /// empty slice if no data is committed.
+    pub fn consume<F: FnOnce(&[T]) -> usize>(&mut self, f: F) {
+        let block = &self.buffer[self.regions.a()];
+        let consumed = std::cmp::min(f(block), block.len());
+        self.decommit(consumed);
+    }
+
+    /// Rotates the committed data so that the element at logical index `k` becomes the front
+    ///
+    /// `k` is taken modulo `committed_len()`; if that leaves the order unchanged, this does
+    /// nothing. Otherwise the data is first compacted to a single `A` region at the start of the
+    /// backing store, and any reservation is cleared
+    pub fn rotate_left(&mut self, k: usize) {
+        let committed = self.committed_len();
+        if committed == 0 || k.is_multiple_of(committed) {
+            return;
+        }
+        self.compact();
+        self.buffer[..committed].rotate_left(k % committed);
+        self.advance_generation(true);
+    }
+
+    /// Decommits up to `length` elements and counts them as dropped
+    fn drop_oldest(&mut self, length: usize) {
+        let committed_before = self.committed_len();
+        self.decommit(length);
+        self.dropped += (committed_before - self.committed_len()) as u64;
+    }
+
+    /// Keeps only the committed elements for which `f` returns `true`, preserving their order
+    ///
+    /// The survivors are compacted into a single `A` region at the start of the backing store,
+    /// and any reservation is cleared
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut f: F)
+    where
+        T: Copy,
+    {
+        self.compact();
+        let mut kept = 0;
+        for index in self.regions.a() {
+            let value = self.buffer[index];
+            if f(&value) {
+                self.buffer[kept] = value;
+                kept += 1;
+            }
+        }
+        self.truncate(kept);
+    }
+
+    /// Removes the committed element at logical `index`, replacing it with the most recently
+    /// committed element
+    ///
+    /// This doesn't preserve ordering, but is O(1). Any reservation is cleared.
+    /// Returns `None` if `index` is out of bounds
+    pub fn swap_remove(&mut self, index: usize) -> Option<T>
+    where
+        T: Copy,
+    {
+        let committed_len = self.committed_len();
+        if index >= committed_len {
+            return None;
+        }
+        let target = self.physical_index(index);
+        let last = self.physical_index(committed_len - 1);
+        let removed = self.buffer[target];
+        self.buffer[target] = self.buffer[last];
+        self.truncate(committed_len - 1);
+        Some(removed)
+    }
+
+    /// Translates a logical index into the committed data to an index in the backing store
+    fn physical_index(&self, index: usize) -> usize {
+        let a = self.regions.a();
+        if index < a.len() {
+            a.start + index
         } else {
-            self.a_start += length;
+            self.regions.b_start + (index - a.len())
         }
     }
 
+    /// Drops the most recently committed elements, keeping the oldest `new_committed_len`
+    ///
+    /// Elements are trimmed from the end of the `B` region first, then from the `A` region.
+    /// Does nothing if `new_committed_len >= committed_len()`; otherwise any reservation is
+    /// cleared.
+    pub fn truncate(&mut self, new_committed_len: usize) {
+        let before = self.committed_bounds();
+        self.regions.truncate(new_committed_len);
+        self.track_generation(before);
+        self.debug_check_invariants();
+    }
+
     /// Number of committed elements
     ///
     /// This approximates the size of the buffer that will be returned on `read()`
     #[inline]
     pub fn committed_len(&self) -> usize {
-        self.a_end - self.a_start + self.b_end - self.b_start
+        self.regions.committed_len()
+    }
+
+    /// Number of committed elements in the `A` and `B` regions respectively
+    #[inline]
+    pub fn committed_lens(&self) -> (usize, usize) {
+        (self.regions.a().len(), self.regions.b().len())
+    }
+
+    /// Number of committed elements in the `A` region
+    ///
+    /// This is the size of the block returned by `read()`, and equals `committed_len()` unless
+    /// the buffer is wrapped
+    #[inline]
+    pub fn contiguous_committed_len(&self) -> usize {
+        self.regions.a().len()
+    }
+
+    /// Number of elements in the largest contiguous block of free space
+    ///
+    /// This is the most that can be returned by the next call to `reserve()`
+    #[inline]
+    pub fn contiguous_free_len(&self) -> usize {
+        self.regions.free_block(self.len()).1
+    }
+
+    /// Number of elements that are neither committed nor reserved
+    ///
+    /// This may be split between the start and end of the backing store; see
+    /// [`is_fragmented()`](#method.is_fragmented)
+    #[inline]
+    pub fn free_len(&self) -> usize {
+        self.len() - self.committed_len() - self.reserved_len()
+    }
+
+    /// Size of the contiguous free block that would be available after
+    /// [`compact()`](#method.compact), without moving any data
+    ///
+    /// Unlike [`contiguous_free_len()`](#method.contiguous_free_len), this counts free space on
+    /// both sides of the `A` region. Slots held by an active reservation are not counted, so
+    /// this is `len() - committed_len() - reserved_len()`, even though `compact()` itself would
+    /// discard the reservation
+    #[inline]
+    pub fn compacted_free_len(&self) -> usize {
+        self.len() - self.committed_len() - self.reserved_len()
+    }
+
+    /// Whether some free space lies outside the largest contiguous free block
+    ///
+    /// When fragmented, `free_len() > contiguous_free_len()`, so `compact()` would recover a
+    /// larger block for reserving
+    pub fn is_fragmented(&self) -> bool {
+        self.free_len() > self.contiguous_free_len()
+    }
+
+    /// The `(start, end)` index ranges of the backing store that are neither committed nor
+    /// reserved, in ascending order
+    ///
+    /// Intended for diagnostics; empty ranges are not included
+    pub fn free_regions(&self) -> Vec<(usize, usize)> {
+        let regions = &self.regions;
+        let mut used = vec![
+            regions.a(),
+            regions.b(),
+            regions.reserved(),
+            regions.reserve_wrap_start..regions.reserve_wrap_end,
+        ];
+        used.retain(|range| !range.is_empty());
+        used.sort_by_key(|range| range.start);
+        let mut free = Vec::new();
+        let mut position = 0;
+        for range in used {
+            if range.start > position {
+                free.push((position, range.start));
+            }
+            position = range.end;
+        }
+        if position < self.len() {
+            free.push((position, self.len()));
+        }
+        free
     }
 
-    /// Number of reserved elements
+    /// Number of reserved elements, across both parts of a vectored reservation
     ///
     /// This is the amount of available space for writing data to the buffer
     #[inline]
     pub fn reserved_len(&self) -> usize {
-        self.reserve_end - self.reserve_start
+        self.regions.reserved_len()
     }
 
     /// Size of the backing store
     ///
-    /// Uses `len() * size_of(T) + 6 * size_of(usize)` memory overall
+    /// Uses about `capacity() * size_of(T)` memory, plus a few `usize` indices
     #[inline]
     pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Number of elements the backing store can hold without reallocating
+    ///
+    /// This is at least `len()`
+    #[inline]
+    pub fn capacity(&self) -> usize {
         self.buffer.capacity()
     }
 
+    /// Allocates room for at least `additional` more elements in the backing store
+    ///
+    /// Only `capacity()` is affected; `len()` and all regions are unchanged. This lets a later
+    /// `reset()` or auto-grow expand the buffer without allocating.
+    pub fn reserve_capacity(&mut self, additional: usize) {
+        self.buffer.reserve(additional);
+    }
+
+    /// Whether committed data is split across the `A` and `B` regions
+    ///
+    /// When wrapped, `read()` only returns the `A` part of the committed data
+    #[inline]
+    pub fn is_wrapped(&self) -> bool {
+        self.regions.is_wrapped()
+    }
+
+    /// Start and end indices of the `A` region in the backing store
+    #[inline]
+    pub fn a_region(&self) -> (usize, usize) {
+        (self.regions.a_start, self.regions.a_end)
+    }
+
+    /// Start and end indices of the `B` region in the backing store
+    #[inline]
+    pub fn b_region(&self) -> (usize, usize) {
+        (self.regions.b_start, self.regions.b_end)
+    }
+
+    /// Start and end indices of the reserved region in the backing store
+    ///
+    /// For a vectored reservation, this only covers the first part
+    #[inline]
+    pub fn reserved_region(&self) -> (usize, usize) {
+        (self.regions.reserve_start, self.regions.reserve_end)
+    }
+
     /// Whether any space has been reserved or committed in the buffer
     #[inline]
     pub fn is_empty(&self) -> bool {
         self.reserved_len() == 0 && self.committed_len() == 0
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Sets the committed length at which
+    /// [`over_high_watermark()`](#method.over_high_watermark) starts returning `true`
+    ///
+    /// A `threshold` of `0` disables the watermark
+    pub fn set_high_watermark(&mut self, threshold: usize) {
+        self.high_watermark = threshold;
+    }
 
-    #[test]
-    fn create_u32() {
-        let _: BipBuffer<u32> = BipBuffer::new(3);
+    /// Whether the committed length has reached the high watermark
+    ///
+    /// Always `false` if no watermark is set
+    #[inline]
+    pub fn over_high_watermark(&self) -> bool {
+        self.high_watermark > 0 && self.committed_len() >= self.high_watermark
     }
-    #[test]
-    fn create_u8() {
-        let _: BipBuffer<u8> = BipBuffer::new(8);
+
+    /// Fraction of the backing store holding committed data, from `0.0` to `1.0`
+    ///
+    /// Always `0.0` for a zero-length buffer
+    pub fn utilization(&self) -> f64 {
+        if self.buffer.is_empty() {
+            return 0.0;
+        }
+        self.committed_len() as f64 / self.len() as f64
     }
-    #[test]
-    fn read_empty() {
-        let mut buffer: BipBuffer<u8> = BipBuffer::new(3);
-        let block = buffer.read();
-        assert_eq!(block, None);
+
+    /// Cumulative counters of elements committed and decommitted, `NoSpace` errors, and wraps
+    #[inline]
+    pub fn stats(&self) -> BipBufferStats {
+        self.stats
+    }
+
+    /// Number of elements discarded without being read, by
+    /// [`push_slice_lossy()`](#method.push_slice_lossy) or the
+    /// [`set_max_committed()`](#method.set_max_committed) limit
+    ///
+    /// Elements removed by `decommit()` and similar calls are not counted
+    #[inline]
+    pub fn dropped_count(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Resets [`dropped_count()`](#method.dropped_count) to zero
+    pub fn reset_dropped_count(&mut self) {
+        self.dropped = 0;
+    }
+
+    /// Counter advanced by every operation that changes the committed regions or their
+    /// contents, such as commits, decommits, compaction and truncation
+    ///
+    /// Comparing generations tells whether committed data may have changed since an earlier
+    /// observation. Reservations alone don't advance it, and neither do calls that leave the
+    /// committed data unchanged, such as `commit(0)`, `decommit(0)` or decommitting an empty
+    /// buffer.
+    #[inline]
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Saves the current region boundaries, so that data decommitted afterwards can be
+    /// recovered with [`restore()`](#method.restore)
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            regions: self.regions,
+            overwrites: self.overwrites,
+        }
+    }
+
+    /// Returns the committed regions to the boundaries saved in `checkpoint`, undoing any
+    /// decommits since
+    ///
+    /// The current reservation, if any, is kept.
+    /// Returns [`Error`](struct.Error.html) without changing the buffer if anything other than
+    /// decommitting has happened since the checkpoint was taken, such as a reservation, commit,
+    /// compaction or [`drain()`](#method.drain) that may have overwritten the captured slots
+    pub fn restore(&mut self, checkpoint: Checkpoint) -> Result<(), Error> {
+        let mut regions = checkpoint.regions;
+        regions.reserve_start = self.regions.reserve_start;
+        regions.reserve_end = self.regions.reserve_end;
+        regions.reserve_wrap_start = self.regions.reserve_wrap_start;
+        regions.reserve_wrap_end = self.regions.reserve_wrap_end;
+        if checkpoint.overwrites != self.overwrites || regions.check_invariants(self.len()).is_err()
+        {
+            return Err(ErrorKind::StaleCheckpoint.into());
+        }
+        if self.committed_bounds() != (regions.a(), regions.b()) {
+            self.advance_generation(false);
+        }
+        self.regions = regions;
+        Ok(())
+    }
+
+    /// Advances the generation, also counting an overwrite unless only decommitting
+    fn advance_generation(&mut self, overwrite: bool) {
+        self.generation += 1;
+        if overwrite {
+            self.overwrites += 1;
+        }
+    }
+
+    /// The committed `A` and `B` regions, for detecting changes with
+    /// [`track_generation()`](#method.track_generation)
+    fn committed_bounds(&self) -> (Range<usize>, Range<usize>) {
+        (self.regions.a(), self.regions.b())
+    }
+
+    /// Advances the generation if the committed regions differ from `before`
+    fn track_generation(&mut self, before: (Range<usize>, Range<usize>)) {
+        if self.committed_bounds() != before {
+            self.advance_generation(true);
+        }
+    }
+
+    /// Verifies that the region indices are consistent with each other and the backing store
+    ///
+    /// Returns a description of the first violation found. This is always `Ok` for buffers
+    /// manipulated only through their public methods, so it's mainly useful in tests.
+    pub fn check_invariants(&self) -> Result<(), &'static str> {
+        self.regions.check_invariants(self.len())
+    }
+
+    /// Verifies the relationships between region indices in debug builds
+    #[inline]
+    fn debug_check_invariants(&self) {
+        self.regions.debug_check_invariants(self.len());
+    }
+
+    /// Consumes the buffer, converting each committed element with `f`
+    ///
+    /// The new buffer has the same length and settings, with the mapped elements committed in
+    /// logical order at the start of a single `A` region. All other slots hold `U::default()`.
+    pub fn map<U: Default, F: FnMut(T) -> U>(self, mut f: F) -> BipBuffer<U> {
+        let mut mapped = BipBuffer::new(self.len());
+        mapped.high_watermark = self.high_watermark;
+        mapped.auto_grow = self.auto_grow;
+        mapped.debug_overlap_checks = self.debug_overlap_checks;
+        mapped.max_committed = self.max_committed;
+        mapped.regions.a_end = self.committed_len();
+        for (slot, value) in mapped.buffer.iter_mut().zip(self) {
+            *slot = f(value);
+        }
+        mapped
+    }
+
+    /// Returns an iterator over the committed elements in logical order
+    pub fn iter(&self) -> Iter<'_, T> {
+        let (a, b) = self.committed_slices();
+        Iter {
+            inner: a.iter().chain(b.iter()),
+        }
+    }
+
+    /// Returns an iterator that extracts complete `delimiter`-terminated frames from the
+    /// committed data
+    ///
+    /// Each frame is yielded without its delimiter, and decommitted along with the delimiter.
+    /// Iteration stops when no complete frame remains, leaving any trailing partial frame
+    /// committed.
+    pub fn frames(&mut self, delimiter: T) -> Frames<'_, T>
+    where
+        T: Copy + PartialEq,
+    {
+        Frames {
+            buffer: self,
+            delimiter,
+        }
+    }
+
+    /// Returns an iterator that moves committed elements out of the buffer in logical order
+    ///
+    /// Each element is moved out as it is yielded, leaving `T::default()` in its slot. The
+    /// yielded elements are decommitted together when the iterator is dropped; if it is dropped
+    /// early, the rest remain committed.
+    pub fn drain(&mut self) -> Drain<'_, T> {
+        Drain {
+            buffer: self,
+            drained: 0,
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for BipBuffer<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let alternate = fmt.alternate();
+        let mut debug = fmt.debug_struct("BipBuffer");
+        debug
+            .field("len", &self.buffer.len())
+            .field("committed_len", &self.regions.committed_len())
+            .field("reserved_len", &self.regions.reserved_len())
+            .field("wrapped", &self.regions.is_wrapped())
+            .field("a", &&self.buffer[self.regions.a()])
+            .field("b", &&self.buffer[self.regions.b()]);
+        if alternate {
+            debug.field("regions", &self.regions);
+        }
+        debug.finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for BipBuffer<T> {
+    /// Compares committed contents in logical order, ignoring physical layout and reservations
+    fn eq(&self, other: &BipBuffer<T>) -> bool {
+        self.regions.committed_len() == other.regions.committed_len()
+            && self.buffer[self.regions.a()]
+                .iter()
+                .chain(&self.buffer[self.regions.b()])
+                .eq(other.buffer[other.regions.a()]
+                    .iter()
+                    .chain(&other.buffer[other.regions.b()]))
+    }
+}
+
+impl<T: Eq> Eq for BipBuffer<T> {}
+
+impl<T: Hash> Hash for BipBuffer<T> {
+    /// Hashes committed contents in logical order, consistent with `PartialEq`
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.regions.committed_len());
+        for element in self.buffer[self.regions.a()]
+            .iter()
+            .chain(&self.buffer[self.regions.b()])
+        {
+            element.hash(state);
+        }
+    }
+}
+
+impl<T: Default> From<Vec<T>> for BipBuffer<T> {
+    /// Uses `vec` as the backing store, with all of its capacity free
+    ///
+    /// Existing elements are kept as the initial contents of their slots but are not committed;
+    /// any spare capacity is filled with `T::default()`.
+    fn from(mut vec: Vec<T>) -> BipBuffer<T> {
+        let capacity = vec.capacity();
+        vec.resize_with(capacity, Default::default);
+        BipBuffer::from_backing(vec)
+    }
+}
+
+impl<T> From<BipBuffer<T>> for Vec<T> {
+    /// Returns the whole backing store, regardless of which slots are committed
+    fn from(buffer: BipBuffer<T>) -> Vec<T> {
+        buffer.buffer
+    }
+}
+
+impl BipBuffer<u8> {
+    /// Reads bytes from `src` directly into the buffer, committing as many as were read
+    ///
+    /// Reserves the largest available contiguous block and passes it to a single call of
+    /// `src.read()`. Returns the number of bytes committed, which is `0` if the buffer is full
+    /// or `src` has reached EOF. If `src` returns an error, nothing is committed.
+    pub fn read_from<R: Read>(&mut self, src: &mut R) -> io::Result<usize> {
+        let reserved = match self.reserve_all() {
+            Ok(reserved) => reserved,
+            Err(_) => return Ok(0),
+        };
+        let length = reserved.len();
+        match src.read(reserved) {
+            Ok(count) => {
+                let count = std::cmp::min(count, length);
+                self.commit(count);
+                Ok(count)
+            }
+            Err(err) => {
+                self.commit(0);
+                Err(err)
+            }
+        }
+    }
+
+    /// Writes committed bytes to `dst`, decommitting as many as were accepted
+    ///
+    /// Passes the contiguous block returned by `read()` to a single call of `dst.write()`.
+    /// Returns the number of bytes decommitted, which is `0` if the buffer is empty. If `dst`
+    /// returns an error, nothing is decommitted.
+    pub fn write_to<W: Write>(&mut self, dst: &mut W) -> io::Result<usize> {
+        let block = match self.read() {
+            Some(block) => block,
+            None => return Ok(0),
+        };
+        let count = dst.write(block)?;
+        self.decommit(count);
+        Ok(count)
+    }
+
+    /// Returns an iterator over the committed bytes as fixed-size records of `S` bytes, in logical
+    /// order
+    ///
+    /// A trailing partial record is not yielded. A record that straddles the wrap between the
+    /// `A` and `B` regions can't be borrowed as one array, so it is skipped; call
+    /// [`compact()`](#method.compact) first if all records are needed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `S` is zero
+    pub fn as_records<const S: usize>(&self) -> impl Iterator<Item = &[u8; S]> {
+        assert!(S > 0, "record size must be nonzero");
+        let (a, b) = self.committed_slices();
+        let straddling = (S - a.len() % S) % S;
+        let b = b.get(straddling..).unwrap_or(&[]);
+        a.chunks_exact(S)
+            .chain(b.chunks_exact(S))
+            .map(|record| <&[u8; S]>::try_from(record).unwrap())
+    }
+
+    /// Writes `data` preceded by its length as a 4-byte big-endian prefix, committing the whole
+    /// frame at once
+    ///
+    /// Returns the number of bytes committed, including the prefix. A
+    /// [`set_max_committed()`](#method.set_max_committed) limit is treated as a capacity: since
+    /// enforcing it would drop bytes from the middle of earlier frames, frames that would push
+    /// the committed length over the limit are refused instead.
+    /// Returns [`Error`](struct.Error.html) without committing anything if the frame does not
+    /// fit in a single contiguous block or under the committed limit, or if `data` is longer
+    /// than `u32::MAX` bytes
+    pub fn push_framed(&mut self, data: &[u8]) -> Result<usize, Error> {
+        if data.len() > u32::MAX as usize {
+            return Err(ErrorKind::NoSpace.into());
+        }
+        let total = FRAME_PREFIX_LEN + data.len();
+        if self.max_committed > 0 && self.committed_len() + total > self.max_committed {
+            self.stats.no_space_errors += 1;
+            return Err(ErrorKind::NoSpace.into());
+        }
+        let reserved = self.reserve_between(total, total)?;
+        reserved[..FRAME_PREFIX_LEN].copy_from_slice(&(data.len() as u32).to_be_bytes());
+        reserved[FRAME_PREFIX_LEN..].copy_from_slice(data);
+        self.commit(total);
+        Ok(total)
+    }
+
+    /// Removes and returns the payload of the next frame written by
+    /// [`push_framed()`](#method.push_framed)
+    ///
+    /// Returns `None`, leaving the buffer unchanged, if a complete frame is not yet committed
+    pub fn read_framed(&mut self) -> Option<Vec<u8>> {
+        let prefix = self.peek_n(FRAME_PREFIX_LEN)?;
+        let length = u32::from_be_bytes([prefix[0], prefix[1], prefix[2], prefix[3]]) as usize;
+        let mut frame = self.peek_n(FRAME_PREFIX_LEN + length)?;
+        self.decommit(frame.len());
+        frame.drain(..FRAME_PREFIX_LEN);
+        Some(frame)
+    }
+}
+
+/// A draining iterator over the committed elements of a `BipBuffer`
+///
+/// Created by [`drain()`](struct.BipBuffer.html#method.drain)
+#[derive(Debug)]
+pub struct Drain<'a, T: Default> {
+    buffer: &'a mut BipBuffer<T>,
+    /// Number of elements yielded, to be decommitted on drop
+    drained: usize,
+}
+
+impl<'a, T: Default> Iterator for Drain<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.drained == self.buffer.committed_len() {
+            return None;
+        }
+        let index = self.buffer.physical_index(self.drained);
+        if self.drained == 0 {
+            // Slots are reset as they are drained, so restoring an earlier checkpoint would
+            // expose the defaults instead of the original data
+            self.buffer.overwrites += 1;
+        }
+        self.drained += 1;
+        Some(std::mem::take(&mut self.buffer.buffer[index]))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.buffer.committed_len() - self.drained;
+        (len, Some(len))
+    }
+}
+
+impl<'a, T: Default> Drop for Drain<'a, T> {
+    fn drop(&mut self) {
+        self.buffer.decommit(self.drained);
+    }
+}
+
+impl<'a, T: Default> ExactSizeIterator for Drain<'a, T> {}
+
+/// An iterator over the delimited frames in a `BipBuffer`
+///
+/// Created by [`frames()`](struct.BipBuffer.html#method.frames)
+#[derive(Debug)]
+pub struct Frames<'a, T> {
+    buffer: &'a mut BipBuffer<T>,
+    delimiter: T,
+}
+
+impl<'a, T: Default + Copy + PartialEq> Iterator for Frames<'a, T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Vec<T>> {
+        let delimiter = self.delimiter;
+        let position = self.buffer.iter().position(|&value| value == delimiter)?;
+        let frame = self.buffer.iter().take(position).copied().collect();
+        self.buffer.decommit(position + 1);
+        Some(frame)
+    }
+}
+
+/// An iterator over the committed elements of a `BipBuffer`
+///
+/// Created by [`iter()`](struct.BipBuffer.html#method.iter)
+#[derive(Debug)]
+pub struct Iter<'a, T> {
+    inner: Chain<slice::Iter<'a, T>, slice::Iter<'a, T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        self.inner.next_back()
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+impl<'a, T: Default> IntoIterator for &'a BipBuffer<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+/// An iterator that moves the committed elements out of a `BipBuffer`
+///
+/// Created by the `into_iter()` method of `BipBuffer`
+#[derive(Debug)]
+pub struct IntoIter<T> {
+    inner: vec::IntoIter<T>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.inner.next_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T: Default> IntoIterator for BipBuffer<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    /// Consumes the buffer, yielding its committed elements in logical order
+    ///
+    /// Reserved and free slots are dropped along with the backing store
+    fn into_iter(mut self) -> IntoIter<T> {
+        self.compact();
+        let mut buffer = self.buffer;
+        buffer.truncate(self.regions.committed_len());
+        IntoIter {
+            inner: buffer.into_iter(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn create_u32() {
+        let _: BipBuffer<u32> = BipBuffer::new(3);
+    }
+    #[test]
+    fn create_u8() {
+        let _: BipBuffer<u8> = BipBuffer::new(8);
+    }
+    #[test]
+    fn read_empty() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(3);
+        let block = buffer.read();
+        assert_eq!(block, None);
     }
     #[test]
     fn read_uncommitted() {
@@ -349,4 +1588,1583 @@ mod tests {
         buffer.clear();
         assert_eq!(buffer.committed_len(), 0);
     }
+    #[test]
+    fn drain_wrapped() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(4);
+        {
+            let reserved = buffer.reserve(4).unwrap();
+            reserved.copy_from_slice(&[1, 2, 3, 4]);
+        }
+        buffer.commit(4);
+        buffer.decommit(2);
+        {
+            let reserved = buffer.reserve(2).unwrap();
+            reserved.copy_from_slice(&[5, 6]);
+        }
+        buffer.commit(2);
+        let mut drain = buffer.drain();
+        assert_eq!(drain.len(), 4);
+        assert_eq!(drain.next(), Some(3));
+        assert_eq!(drain.len(), 3);
+        assert_eq!(drain.next(), Some(4));
+        assert_eq!(drain.len(), 2);
+        assert_eq!(drain.collect::<Vec<_>>(), vec![5, 6]);
+        assert_eq!(buffer.committed_len(), 0);
+        assert_eq!(buffer.read(), None);
+    }
+    #[test]
+    fn drain_dropped_early() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(4);
+        {
+            let reserved = buffer.reserve(3).unwrap();
+            reserved.copy_from_slice(&[1, 2, 3]);
+        }
+        buffer.commit(3);
+        assert_eq!(buffer.drain().next(), Some(1));
+        assert_eq!(buffer.committed_len(), 2);
+        assert_eq!(buffer.read().unwrap(), &[2, 3]);
+    }
+    #[test]
+    fn read_from_slice() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(4);
+        let mut src: &[u8] = &[1, 2, 3, 4, 5, 6];
+        assert_eq!(buffer.read_from(&mut src).unwrap(), 4);
+        assert_eq!(buffer.read_from(&mut src).unwrap(), 0);
+        assert_eq!(buffer.read().unwrap(), &[1, 2, 3, 4]);
+        buffer.decommit(3);
+        assert_eq!(buffer.read_from(&mut src).unwrap(), 2);
+        assert_eq!(buffer.committed_len(), 3);
+        assert_eq!(buffer.reserved_len(), 0);
+        buffer.decommit(1);
+        assert_eq!(buffer.read().unwrap(), &[5, 6]);
+        assert_eq!(buffer.read_from(&mut src).unwrap(), 0);
+        assert_eq!(buffer.committed_len(), 2);
+    }
+    #[test]
+    fn write_to_vec() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(4);
+        let mut dst = Vec::new();
+        assert_eq!(buffer.write_to(&mut dst).unwrap(), 0);
+        {
+            let reserved = buffer.reserve(4).unwrap();
+            reserved.copy_from_slice(&[1, 2, 3, 4]);
+        }
+        buffer.commit(4);
+        assert_eq!(buffer.write_to(&mut dst).unwrap(), 4);
+        assert_eq!(dst, vec![1, 2, 3, 4]);
+        assert_eq!(buffer.committed_len(), 0);
+    }
+    #[test]
+    fn write_to_partial() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(4);
+        {
+            let reserved = buffer.reserve(4).unwrap();
+            reserved.copy_from_slice(&[1, 2, 3, 4]);
+        }
+        buffer.commit(4);
+        let mut storage = [0u8; 3];
+        {
+            let mut dst: &mut [u8] = &mut storage;
+            assert_eq!(buffer.write_to(&mut dst).unwrap(), 3);
+        }
+        assert_eq!(storage, [1, 2, 3]);
+        assert_eq!(buffer.committed_len(), 1);
+        assert_eq!(buffer.read().unwrap(), &[4]);
+    }
+    #[test]
+    fn with_default_fill() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::with_default(4, 0xFF);
+        assert_eq!(buffer.len(), 4);
+        assert!(buffer.is_empty());
+        {
+            let reserved = buffer.reserve(4).unwrap();
+            assert_eq!(reserved, &[0xFF; 4]);
+            reserved[0] = 1;
+        }
+        buffer.commit(1);
+        let reserved = buffer.reserve(3).unwrap();
+        assert_eq!(reserved, &[0xFF; 3]);
+    }
+    #[test]
+    fn committed_slices_wrapped() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(4);
+        assert_eq!(buffer.committed_slices(), (&[][..], &[][..]));
+        {
+            let reserved = buffer.reserve(4).unwrap();
+            reserved.copy_from_slice(&[1, 2, 3, 4]);
+        }
+        buffer.commit(4);
+        buffer.decommit(2);
+        {
+            let reserved = buffer.reserve(1).unwrap();
+            reserved[0] = 5;
+        }
+        buffer.commit(1);
+        let (a, b) = buffer.committed_slices();
+        assert_eq!(a, &[3, 4]);
+        assert_eq!(b, &[5]);
+        assert_eq!([a, b].concat(), vec![3, 4, 5]);
+    }
+    #[test]
+    #[cfg(debug_assertions)]
+    fn invariants_hold_over_cycle() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(4);
+        for round in 0..8 {
+            let reserved = buffer.reserve(3).unwrap();
+            let count = reserved.len();
+            buffer.commit(count);
+            buffer.decommit(round % 3);
+            buffer.commit(0);
+            if buffer.committed_len() == buffer.len() {
+                buffer.decommit(buffer.len());
+            }
+        }
+        buffer.clear();
+        assert!(buffer.is_empty());
+    }
+    #[test]
+    fn reserve_up_to() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(4);
+        {
+            let reserved = buffer.reserve_up_to(3);
+            assert_eq!(reserved.len(), 3);
+            reserved.copy_from_slice(&[1, 2, 3]);
+        }
+        buffer.commit(3);
+        assert_eq!(buffer.reserve_up_to(8).len(), 1);
+        buffer.commit(1);
+        assert!(buffer.reserve_up_to(1).is_empty());
+        assert_eq!(buffer.reserved_len(), 0);
+        buffer.commit(0);
+        assert_eq!(buffer.committed_len(), 4);
+        assert_eq!(buffer.read().unwrap()[..3], [1, 2, 3]);
+    }
+    #[test]
+    fn reserved_after_reserve() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(4);
+        assert_eq!(buffer.reserved(), None);
+        buffer.reserve(3).unwrap();
+        {
+            let reserved = buffer.reserved().unwrap();
+            assert_eq!(reserved.len(), 3);
+            reserved.copy_from_slice(&[9, 8, 7]);
+        }
+        assert_eq!(buffer.reserved_len(), 3);
+        buffer.commit(3);
+        assert_eq!(buffer.reserved(), None);
+        assert_eq!(buffer.read().unwrap(), &[9, 8, 7]);
+    }
+    #[test]
+    fn reserve_all() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(4);
+        assert_eq!(buffer.contiguous_free_len(), 4);
+        assert_eq!(buffer.reserve_all().unwrap().len(), 4);
+        buffer.commit(3);
+        assert_eq!(buffer.contiguous_free_len(), 1);
+        assert_eq!(buffer.reserve_all().unwrap().len(), 1);
+        buffer.commit(1);
+        assert_eq!(buffer.contiguous_free_len(), 0);
+        assert!(buffer.reserve_all().is_err());
+    }
+    #[test]
+    fn to_vec_wrapped() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(4);
+        assert!(buffer.to_vec().is_empty());
+        {
+            let reserved = buffer.reserve(4).unwrap();
+            reserved.copy_from_slice(&[1, 2, 3, 4]);
+        }
+        buffer.commit(4);
+        buffer.decommit(1);
+        {
+            let reserved = buffer.reserve(1).unwrap();
+            reserved[0] = 5;
+        }
+        buffer.commit(1);
+        let data = buffer.to_vec();
+        assert_eq!(data, vec![2, 3, 4, 5]);
+        assert_eq!(data.len(), buffer.committed_len());
+    }
+    #[test]
+    fn reserve_with_partial_commit() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(4);
+        let result = buffer.reserve_with(4, |reserved| {
+            assert_eq!(reserved.len(), 4);
+            reserved[0] = 10;
+            reserved[1] = 20;
+            (2, "parsed")
+        });
+        assert_eq!(result.unwrap(), "parsed");
+        assert_eq!(buffer.committed_len(), 2);
+        assert_eq!(buffer.reserved_len(), 0);
+        assert_eq!(buffer.read().unwrap(), &[10, 20]);
+        let clamped = buffer.reserve_with(1, |reserved| (reserved.len() + 5, ()));
+        assert!(clamped.is_ok());
+        assert_eq!(buffer.committed_len(), 3);
+        buffer.reserve_with(1, |_| (1, ())).unwrap();
+        assert!(buffer.reserve_with(1, |_| (1, ())).is_err());
+    }
+    #[test]
+    fn high_watermark() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(8);
+        buffer.reserve(8).unwrap();
+        buffer.commit(2);
+        assert!(!buffer.over_high_watermark());
+        buffer.set_high_watermark(4);
+        assert!(!buffer.over_high_watermark());
+        buffer.reserve(2).unwrap();
+        buffer.commit(2);
+        assert!(buffer.over_high_watermark());
+        buffer.reserve(2).unwrap();
+        buffer.commit(2);
+        assert!(buffer.over_high_watermark());
+        buffer.decommit(3);
+        assert!(!buffer.over_high_watermark());
+        buffer.set_high_watermark(0);
+        buffer.reserve(2).unwrap();
+        buffer.commit(2);
+        assert!(!buffer.over_high_watermark());
+    }
+    #[test]
+    fn decommit_across_wrap() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(6);
+        {
+            let reserved = buffer.reserve(6).unwrap();
+            reserved.copy_from_slice(&[1, 2, 3, 4, 5, 6]);
+        }
+        buffer.commit(6);
+        buffer.decommit(3);
+        {
+            let reserved = buffer.reserve(3).unwrap();
+            reserved.copy_from_slice(&[7, 8, 9]);
+        }
+        buffer.commit(3);
+        buffer.decommit(4);
+        assert_eq!(buffer.committed_len(), 2);
+        assert_eq!(buffer.read().unwrap(), &[8, 9]);
+        buffer.decommit(10);
+        assert_eq!(buffer.committed_len(), 0);
+        assert_eq!(buffer.read(), None);
+        assert_eq!(buffer.reserve(8).unwrap().len(), 6);
+    }
+    #[test]
+    fn reset_resizes() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(8);
+        buffer.reserve(8).unwrap();
+        buffer.commit(5);
+        let capacity = buffer.capacity();
+        buffer.reset(4);
+        assert_eq!(buffer.len(), 4);
+        assert_eq!(buffer.capacity(), capacity);
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.reserve(8).unwrap(), &[0; 4]);
+        buffer.commit(4);
+        buffer.reset(16);
+        assert_eq!(buffer.len(), 16);
+        assert!(buffer.capacity() >= 16);
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.reserve(16).unwrap().len(), 16);
+    }
+    #[test]
+    fn region_boundaries() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(8);
+        buffer.reserve(6).unwrap();
+        assert_eq!(buffer.reserved_region(), (0, 6));
+        buffer.commit(6);
+        assert_eq!(buffer.a_region(), (0, 6));
+        assert_eq!(buffer.reserved_region(), (0, 0));
+        buffer.decommit(4);
+        buffer.reserve(3).unwrap();
+        assert_eq!(buffer.reserved_region(), (0, 3));
+        buffer.commit(3);
+        assert_eq!(buffer.a_region(), (4, 6));
+        assert_eq!(buffer.b_region(), (0, 3));
+        buffer.decommit(2);
+        assert_eq!(buffer.a_region(), (0, 3));
+        assert_eq!(buffer.b_region(), (0, 0));
+    }
+    #[test]
+    fn auto_grow_wrapped() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(4);
+        {
+            let reserved = buffer.reserve(4).unwrap();
+            reserved.copy_from_slice(&[1, 2, 3, 4]);
+        }
+        buffer.commit(4);
+        buffer.decommit(2);
+        {
+            let reserved = buffer.reserve(1).unwrap();
+            reserved[0] = 5;
+        }
+        buffer.commit(1);
+        assert_eq!(buffer.reserve(6).unwrap().len(), 1);
+        buffer.set_auto_grow(true);
+        {
+            let reserved = buffer.reserve(6).unwrap();
+            assert_eq!(reserved.len(), 6);
+            reserved.copy_from_slice(&[6, 7, 8, 9, 10, 11]);
+        }
+        assert_eq!(buffer.len(), 16);
+        buffer.commit(6);
+        assert_eq!(buffer.committed_len(), 9);
+        assert_eq!(buffer.read().unwrap(), &[3, 4, 5, 6, 7, 8, 9, 10, 11]);
+    }
+    #[test]
+    fn auto_grow_from_empty() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(0);
+        assert!(buffer.reserve(1).is_err());
+        buffer.set_auto_grow(true);
+        assert_eq!(buffer.reserve(3).unwrap().len(), 3);
+        assert_eq!(buffer.len(), 4);
+        assert_eq!(buffer.reserve(usize::MAX).unwrap().len(), 4);
+    }
+    #[test]
+    fn is_wrapped() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(4);
+        assert!(!buffer.is_wrapped());
+        buffer.reserve(4).unwrap();
+        buffer.commit(4);
+        buffer.decommit(2);
+        assert!(!buffer.is_wrapped());
+        buffer.reserve(1).unwrap();
+        buffer.commit(1);
+        assert!(buffer.is_wrapped());
+        buffer.decommit(2);
+        assert!(!buffer.is_wrapped());
+    }
+    #[test]
+    fn read_contiguous_wrapped() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(5);
+        assert_eq!(buffer.read_contiguous(), None);
+        {
+            let reserved = buffer.reserve(5).unwrap();
+            reserved.copy_from_slice(&[1, 2, 3, 4, 5]);
+        }
+        buffer.commit(5);
+        buffer.decommit(3);
+        assert_eq!(buffer.read_contiguous().unwrap(), &[4, 5]);
+        assert_eq!(buffer.a_region(), (3, 5));
+        {
+            let reserved = buffer.reserve(2).unwrap();
+            reserved.copy_from_slice(&[6, 7]);
+        }
+        buffer.commit(2);
+        assert_eq!(buffer.read_contiguous().unwrap(), &[4, 5, 6, 7]);
+        assert!(!buffer.is_wrapped());
+        assert_eq!(buffer.a_region(), (0, 4));
+        assert_eq!(buffer.contiguous_free_len(), 1);
+    }
+    #[test]
+    fn compact() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(6);
+        {
+            let reserved = buffer.reserve(4).unwrap();
+            reserved.copy_from_slice(&[1, 2, 3, 4]);
+        }
+        buffer.commit(4);
+        buffer.decommit(3);
+        buffer.reserve(1).unwrap()[0] = 5;
+        buffer.commit(1);
+        assert!(buffer.is_wrapped());
+        assert_eq!(buffer.contiguous_free_len(), 2);
+        buffer.compact();
+        assert_eq!(buffer.a_region(), (0, 2));
+        assert_eq!(buffer.b_region(), (0, 0));
+        assert_eq!(buffer.read().unwrap(), &[4, 5]);
+        assert_eq!(buffer.contiguous_free_len(), 4);
+    }
+    #[test]
+    fn peek_n() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(4);
+        assert_eq!(buffer.peek_n(0), Some(vec![]));
+        assert_eq!(buffer.peek_n(1), None);
+        {
+            let reserved = buffer.reserve(4).unwrap();
+            reserved.copy_from_slice(&[1, 2, 3, 4]);
+        }
+        buffer.commit(4);
+        assert_eq!(buffer.peek_n(2), Some(vec![1, 2]));
+        buffer.decommit(2);
+        {
+            let reserved = buffer.reserve(2).unwrap();
+            reserved.copy_from_slice(&[5, 6]);
+        }
+        buffer.commit(2);
+        assert_eq!(buffer.peek_n(3), Some(vec![3, 4, 5]));
+        assert_eq!(buffer.peek_n(4), Some(vec![3, 4, 5, 6]));
+        assert_eq!(buffer.peek_n(5), None);
+        assert_eq!(buffer.committed_len(), 4);
+    }
+    #[test]
+    fn commit_after_wrap_to_zero() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(4);
+        {
+            let reserved = buffer.reserve(4).unwrap();
+            reserved.copy_from_slice(&[1, 2, 3, 4]);
+        }
+        buffer.commit(4);
+        buffer.decommit(3);
+        {
+            let reserved = buffer.reserve(4).unwrap();
+            reserved.copy_from_slice(&[5, 6, 7]);
+        }
+        assert_eq!(buffer.reserved_region(), (0, 3));
+        buffer.commit(2);
+        assert_eq!(buffer.a_region(), (3, 4));
+        assert_eq!(buffer.b_region(), (0, 2));
+        {
+            let reserved = buffer.reserve(4).unwrap();
+            reserved[0] = 8;
+        }
+        assert_eq!(buffer.reserved_region(), (2, 3));
+        buffer.commit(1);
+        assert_eq!(buffer.read().unwrap(), &[4]);
+        buffer.decommit(1);
+        assert_eq!(buffer.read().unwrap(), &[5, 6, 8]);
+        buffer.decommit(3);
+        {
+            let reserved = buffer.reserve(1).unwrap();
+            reserved[0] = 9;
+        }
+        assert_eq!(buffer.reserved_region(), (0, 1));
+        buffer.commit(1);
+        assert_eq!(buffer.a_region(), (0, 1));
+        assert_eq!(buffer.read().unwrap(), &[9]);
+    }
+    #[test]
+    fn iter_wrapped() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(4);
+        assert_eq!(buffer.iter().next(), None);
+        {
+            let reserved = buffer.reserve(4).unwrap();
+            reserved.copy_from_slice(&[1, 2, 3, 4]);
+        }
+        buffer.commit(4);
+        buffer.decommit(2);
+        buffer.reserve(1).unwrap()[0] = 5;
+        buffer.commit(1);
+        assert_eq!(buffer.iter().len(), 3);
+        assert_eq!(buffer.iter().copied().collect::<Vec<_>>(), vec![3, 4, 5]);
+        let mut sum = 0;
+        for value in &buffer {
+            sum += value;
+        }
+        assert_eq!(sum, 12);
+    }
+    #[test]
+    fn into_iter_wrapped() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(5);
+        {
+            let reserved = buffer.reserve(5).unwrap();
+            reserved.copy_from_slice(&[1, 2, 3, 4, 5]);
+        }
+        buffer.commit(4);
+        buffer.decommit(3);
+        {
+            let reserved = buffer.reserve(2).unwrap();
+            reserved.copy_from_slice(&[6, 7]);
+        }
+        buffer.commit(2);
+        buffer.reserve(1).unwrap();
+        assert!(buffer.is_wrapped());
+        let committed = buffer.committed_len();
+        let data: Vec<u8> = buffer.into_iter().collect();
+        assert_eq!(data.len(), committed);
+        assert_eq!(data, vec![4, 6, 7]);
+    }
+    #[test]
+    fn push_slice_lossy_overflow() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(4);
+        assert_eq!(buffer.push_slice_lossy(&[1, 2, 3]), 3);
+        assert_eq!(buffer.push_slice_lossy(&[4, 5, 6]), 3);
+        assert_eq!(buffer.committed_len(), 4);
+        assert_eq!(buffer.to_vec(), vec![3, 4, 5, 6]);
+        assert_eq!(buffer.push_slice_lossy(&[7]), 1);
+        assert_eq!(buffer.to_vec(), vec![4, 5, 6, 7]);
+    }
+    #[test]
+    fn push_slice_lossy_longer_than_buffer() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(4);
+        buffer.push_slice_lossy(&[1, 2]);
+        assert_eq!(buffer.push_slice_lossy(&[3, 4, 5, 6, 7, 8]), 4);
+        assert_eq!(buffer.to_vec(), vec![5, 6, 7, 8]);
+        let mut empty: BipBuffer<u8> = BipBuffer::new(0);
+        assert_eq!(empty.push_slice_lossy(&[1]), 0);
+    }
+    #[test]
+    fn replace_with_wrapped() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(4);
+        {
+            let reserved = buffer.reserve(4).unwrap();
+            reserved.copy_from_slice(&[1, 2, 3, 4]);
+        }
+        buffer.commit(4);
+        buffer.decommit(2);
+        buffer.reserve(1).unwrap()[0] = 5;
+        buffer.commit(1);
+        assert_eq!(buffer.replace_with(&[6, 7]), vec![3, 4, 5]);
+        assert!(!buffer.is_wrapped());
+        assert_eq!(buffer.read().unwrap(), &[6, 7]);
+        assert_eq!(buffer.replace_with(&[8, 9, 10, 11, 12]), vec![6, 7]);
+        assert_eq!(buffer.read().unwrap(), &[8, 9, 10, 11]);
+        assert_eq!(buffer.replace_with(&[]), vec![8, 9, 10, 11]);
+        assert!(buffer.is_empty());
+    }
+    #[test]
+    fn stats() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(4);
+        assert_eq!(buffer.stats(), BipBufferStats::default());
+        buffer.reserve(4).unwrap();
+        buffer.commit(4);
+        assert!(buffer.reserve(1).is_err());
+        buffer.decommit(3);
+        buffer.reserve(2).unwrap();
+        buffer.commit(2);
+        buffer.reserve(1).unwrap();
+        buffer.commit(1);
+        assert!(buffer.reserve(1).is_err());
+        buffer.decommit(10);
+        buffer.reserve(1).unwrap();
+        buffer.commit(0);
+        assert_eq!(
+            buffer.stats(),
+            BipBufferStats {
+                committed: 7,
+                decommitted: 7,
+                no_space_errors: 2,
+                wraps: 1,
+            }
+        );
+    }
+    #[test]
+    fn reserve_between() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(8);
+        assert_eq!(buffer.reserve_between(2, 6).unwrap().len(), 6);
+        buffer.commit(6);
+        buffer.decommit(3);
+        assert_eq!(buffer.committed_len(), 3);
+        assert_eq!(buffer.contiguous_free_len(), 3);
+        assert!(buffer.reserve_between(4, 5).is_err());
+        assert_eq!(buffer.reserved_len(), 0);
+        assert_eq!(buffer.reserve_between(2, 5).unwrap().len(), 3);
+        assert_eq!(buffer.reserved_region(), (0, 3));
+    }
+    #[test]
+    fn frames_newline() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(16);
+        buffer.push_slice_lossy(b"ab\ncde\nf");
+        {
+            let mut frames = buffer.frames(b'\n');
+            assert_eq!(frames.next(), Some(b"ab".to_vec()));
+            assert_eq!(frames.next(), Some(b"cde".to_vec()));
+            assert_eq!(frames.next(), None);
+        }
+        assert_eq!(buffer.read().unwrap(), b"f");
+        buffer.push_slice_lossy(b"g\n");
+        assert_eq!(
+            buffer.frames(b'\n').collect::<Vec<_>>(),
+            vec![b"fg".to_vec()]
+        );
+        assert!(buffer.is_empty());
+    }
+    #[test]
+    fn frames_wrapped() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(6);
+        buffer.push_slice_lossy(b"xxxab");
+        buffer.decommit(3);
+        buffer.push_slice_lossy(b"c\nd");
+        assert!(buffer.is_wrapped());
+        assert_eq!(
+            buffer.frames(b'\n').collect::<Vec<_>>(),
+            vec![b"abc".to_vec()]
+        );
+        assert_eq!(buffer.to_vec(), b"d".to_vec());
+    }
+    #[test]
+    fn truncate() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(6);
+        buffer.push_slice_lossy(&[1, 2, 3, 4, 5, 6]);
+        buffer.decommit(3);
+        buffer.push_slice_lossy(&[7, 8, 9]);
+        assert!(buffer.is_wrapped());
+        buffer.truncate(10);
+        assert_eq!(buffer.to_vec(), vec![4, 5, 6, 7, 8, 9]);
+        buffer.truncate(5);
+        assert_eq!(buffer.to_vec(), vec![4, 5, 6, 7, 8]);
+        assert_eq!(buffer.b_region(), (0, 2));
+        buffer.truncate(3);
+        assert_eq!(buffer.to_vec(), vec![4, 5, 6]);
+        assert!(!buffer.is_wrapped());
+        buffer.truncate(1);
+        assert_eq!(buffer.to_vec(), vec![4]);
+        assert_eq!(buffer.a_region(), (3, 4));
+        buffer.reserve(1).unwrap();
+        buffer.truncate(0);
+        assert!(buffer.is_empty());
+        buffer.push_slice_lossy(&[10, 11]);
+        assert_eq!(buffer.read().unwrap(), &[10, 11]);
+    }
+    #[test]
+    fn debug_format() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::with_default(6, 0xEE);
+        buffer.push_slice_lossy(&[1, 2, 3, 4, 5]);
+        buffer.decommit(3);
+        buffer.push_slice_lossy(&[6]);
+        buffer.reserve(1).unwrap();
+        let output = format!("{:?}", buffer);
+        assert_eq!(
+            output,
+            "BipBuffer { len: 6, committed_len: 3, reserved_len: 1, wrapped: true, \
+             a: [4, 5], b: [6] }"
+        );
+        assert!(!output.contains("238"));
+        let alternate = format!("{:#?}", buffer);
+        assert!(alternate.contains("committed_len: 3"));
+        assert!(alternate.contains("a_start: 3"));
+        assert!(alternate.contains("reserve_end: 2"));
+    }
+    #[test]
+    fn reserve_vectored_fragmented() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(8);
+        buffer.push_slice_lossy(&[1, 2, 3, 4, 5, 6]);
+        buffer.decommit(3);
+        assert_eq!(buffer.contiguous_free_len(), 3);
+        {
+            let (first, second) = buffer.reserve_vectored(8);
+            assert_eq!(first.len() + second.len(), 5);
+            first.copy_from_slice(&[7, 8]);
+            second.copy_from_slice(&[9, 10, 11]);
+        }
+        assert_eq!(buffer.reserved_len(), 5);
+        buffer.commit(4);
+        assert_eq!(buffer.reserved_len(), 0);
+        assert_eq!(buffer.a_region(), (3, 8));
+        assert_eq!(buffer.b_region(), (0, 2));
+        assert_eq!(buffer.to_vec(), vec![4, 5, 6, 7, 8, 9, 10]);
+    }
+    #[test]
+    fn reserve_vectored_contiguous() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(4);
+        {
+            let (first, second) = buffer.reserve_vectored(3);
+            assert_eq!(first.len(), 3);
+            assert!(second.is_empty());
+        }
+        buffer.commit(3);
+        {
+            let (first, second) = buffer.reserve_vectored(3);
+            assert_eq!(first.len(), 1);
+            assert!(second.is_empty());
+        }
+        buffer.commit(1);
+        let (first, second) = buffer.reserve_vectored(1);
+        assert!(first.is_empty() && second.is_empty());
+    }
+    #[test]
+    fn contiguous_committed_len() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(4);
+        assert_eq!(buffer.contiguous_committed_len(), 0);
+        buffer.push_slice_lossy(&[1, 2, 3, 4]);
+        buffer.decommit(2);
+        assert_eq!(buffer.contiguous_committed_len(), buffer.committed_len());
+        buffer.push_slice_lossy(&[5]);
+        assert_eq!(buffer.contiguous_committed_len(), 2);
+        assert_eq!(buffer.committed_len(), 3);
+    }
+    #[test]
+    fn reserve_zeroed() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(4);
+        buffer.push_slice_lossy(&[1, 2, 3, 4]);
+        buffer.decommit(4);
+        assert_eq!(buffer.reserve(4).unwrap(), &[1, 2, 3, 4]);
+        assert_eq!(buffer.reserve_zeroed(4).unwrap(), &[0; 4]);
+        buffer.commit(4);
+        assert!(buffer.reserve_zeroed(1).is_err());
+    }
+    #[test]
+    fn committed_lens() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(4);
+        assert_eq!(buffer.committed_lens(), (0, 0));
+        buffer.push_slice_lossy(&[1, 2, 3, 4]);
+        buffer.decommit(1);
+        assert_eq!(buffer.committed_lens(), (3, 0));
+        buffer.push_slice_lossy(&[5]);
+        assert_eq!(buffer.committed_lens(), (3, 1));
+    }
+    #[test]
+    fn reserve_capacity() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(4);
+        buffer.push_slice_lossy(&[1, 2, 3]);
+        buffer.reserve_capacity(12);
+        assert!(buffer.capacity() >= 16);
+        assert_eq!(buffer.len(), 4);
+        assert_eq!(buffer.read().unwrap(), &[1, 2, 3]);
+        assert_eq!(buffer.contiguous_free_len(), 1);
+        let capacity = buffer.capacity();
+        buffer.set_auto_grow(true);
+        assert_eq!(buffer.reserve(5).unwrap().len(), 5);
+        assert_eq!(buffer.len(), 8);
+        assert_eq!(buffer.capacity(), capacity);
+    }
+    #[test]
+    fn vec_round_trip() {
+        let mut vec = Vec::with_capacity(6);
+        vec.extend_from_slice(&[1u8, 2, 3]);
+        let capacity = vec.capacity();
+        let mut buffer: BipBuffer<u8> = vec.into();
+        assert_eq!(buffer.len(), capacity);
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.reserve(3).unwrap(), &[1, 2, 3]);
+        buffer.commit(3);
+        let vec: Vec<u8> = buffer.into();
+        assert_eq!(vec.len(), capacity);
+        assert_eq!(vec.capacity(), capacity);
+        assert_eq!(vec[..3], [1, 2, 3]);
+        let buffer = BipBuffer::from(vec);
+        assert_eq!(buffer.len(), capacity);
+        assert_eq!(buffer.committed_len(), 0);
+    }
+    #[test]
+    fn map_wrapped() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(4);
+        buffer.push_slice_lossy(&[1, 2, 3, 4]);
+        buffer.decommit(2);
+        buffer.push_slice_lossy(&[5]);
+        assert!(buffer.is_wrapped());
+        let mut mapped: BipBuffer<u16> = buffer.map(|value| u16::from(value) * 100);
+        assert_eq!(mapped.len(), 4);
+        assert_eq!(mapped.committed_len(), 3);
+        assert_eq!(mapped.read().unwrap(), &[300, 400, 500]);
+        assert_eq!(mapped.reserve(4).unwrap(), &[0]);
+    }
+    #[test]
+    fn retain_wrapped() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(6);
+        buffer.push_slice_lossy(&[1, 2, 3, 4, 5, 6]);
+        buffer.decommit(3);
+        buffer.push_slice_lossy(&[7, 8]);
+        assert!(buffer.is_wrapped());
+        buffer.retain(|value| value % 2 == 0);
+        assert!(!buffer.is_wrapped());
+        assert_eq!(buffer.committed_len(), 3);
+        assert_eq!(buffer.read().unwrap(), &[4, 6, 8]);
+        buffer.retain(|_| false);
+        assert!(buffer.is_empty());
+    }
+    #[test]
+    fn peek_back() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(4);
+        assert_eq!(buffer.peek_back(), None);
+        buffer.push_slice_lossy(&[1, 2, 3, 4]);
+        assert_eq!(buffer.peek_back(), Some(&4));
+        buffer.decommit(2);
+        buffer.push_slice_lossy(&[5]);
+        assert!(buffer.is_wrapped());
+        assert_eq!(buffer.peek_back(), Some(&5));
+    }
+    #[test]
+    fn peek_front() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(4);
+        assert_eq!(buffer.peek_front(), None);
+        buffer.push_slice_lossy(&[1, 2, 3, 4]);
+        assert_eq!(buffer.peek_front(), Some(&1));
+        buffer.decommit(3);
+        buffer.push_slice_lossy(&[5]);
+        assert_eq!(buffer.peek_front(), Some(&4));
+        buffer.decommit(1);
+        assert_eq!(buffer.peek_front(), Some(&5));
+        buffer.decommit(1);
+        assert_eq!(buffer.peek_front(), None);
+    }
+    #[test]
+    fn commit_and_keep() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(6);
+        assert!(buffer.commit_and_keep(1).is_err());
+        buffer.push_slice_lossy(&[1, 2, 3, 4]);
+        buffer.decommit(3);
+        buffer.reserve(4).unwrap()[0] = 5;
+        {
+            let tail = buffer.commit_and_keep(1).unwrap();
+            assert_eq!(tail.len(), 2);
+            tail.copy_from_slice(&[6, 7]);
+        }
+        assert_eq!(buffer.to_vec(), vec![4, 5]);
+        assert_eq!(buffer.reserved_len(), 2);
+        {
+            let tail = buffer.commit_and_keep(1).unwrap();
+            assert_eq!(tail, &[7]);
+        }
+        assert_eq!(buffer.to_vec(), vec![4, 5, 6]);
+        assert!(buffer.commit_and_keep(5).unwrap().is_empty());
+        assert_eq!(buffer.reserved_len(), 0);
+        assert_eq!(buffer.to_vec(), vec![4, 5, 6, 7]);
+        buffer.decommit(1);
+        buffer.reserve(2).unwrap()[0] = 8;
+        assert_eq!(buffer.reserved_region(), (3, 5));
+        buffer.commit_and_keep(1).unwrap()[0] = 9;
+        buffer.commit(1);
+        assert_eq!(buffer.to_vec(), vec![5, 6, 7, 8, 9]);
+    }
+    #[test]
+    fn is_fragmented() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(8);
+        assert!(!buffer.is_fragmented());
+        assert_eq!(buffer.free_len(), 8);
+        buffer.push_slice_lossy(&[1, 2, 3, 4, 5, 6]);
+        assert!(!buffer.is_fragmented());
+        buffer.decommit(4);
+        buffer.push_slice_lossy(&[7]);
+        assert!(buffer.is_wrapped());
+        assert!(buffer.is_fragmented());
+        assert_eq!(buffer.free_len(), 5);
+        assert!(buffer.free_len() > buffer.contiguous_free_len());
+        buffer.compact();
+        assert!(!buffer.is_fragmented());
+        assert_eq!(buffer.free_len(), buffer.contiguous_free_len());
+        buffer.push_slice_lossy(&[8, 9, 10, 11, 12]);
+        assert_eq!(buffer.free_len(), 0);
+        assert!(!buffer.is_fragmented());
+    }
+    #[test]
+    fn check_invariants() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(8);
+        assert_eq!(buffer.check_invariants(), Ok(()));
+        buffer.push_slice_lossy(&[1, 2, 3, 4, 5, 6]);
+        buffer.decommit(4);
+        buffer.push_slice_lossy(&[7]);
+        assert_eq!(buffer.check_invariants(), Ok(()));
+        buffer.reserve_vectored(8);
+        assert_eq!(buffer.check_invariants(), Ok(()));
+        buffer.commit(2);
+        buffer.truncate(2);
+        assert_eq!(buffer.check_invariants(), Ok(()));
+        buffer.reset(2);
+        assert_eq!(buffer.check_invariants(), Ok(()));
+        buffer.regions.a_end = 3;
+        assert_eq!(
+            buffer.check_invariants(),
+            Err("A region exceeds backing store")
+        );
+    }
+    #[test]
+    fn check_invariants_empty_b_out_of_bounds() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(4);
+        buffer.regions.b_start = 6;
+        buffer.regions.b_end = 6;
+        assert_eq!(
+            buffer.check_invariants(),
+            Err("B region exceeds backing store")
+        );
+    }
+    #[test]
+    fn check_invariants_wrapped_reservation() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(4);
+        buffer.regions.reserve_wrap_end = 5;
+        assert_eq!(
+            buffer.check_invariants(),
+            Err("wrapped reservation exceeds backing store")
+        );
+    }
+    #[test]
+    fn generation() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(4);
+        assert_eq!(buffer.generation(), 0);
+        buffer.reserve(2).unwrap();
+        buffer.commit(0);
+        assert_eq!(buffer.generation(), 0);
+        buffer.push_slice_lossy(&[1, 2, 3, 4]);
+        let observed = buffer.generation();
+        assert_eq!(observed, 1);
+        let a_region = buffer.a_region();
+        buffer.decommit(4);
+        buffer.push_slice_lossy(&[5, 6, 7, 8]);
+        assert_eq!(buffer.a_region(), a_region);
+        assert!(buffer.generation() > observed);
+        buffer.decommit(0);
+        assert_eq!(buffer.generation(), 3);
+    }
+    #[test]
+    fn append_wrapped() {
+        let mut source: BipBuffer<u8> = BipBuffer::new(4);
+        source.push_slice_lossy(&[1, 2, 3, 4]);
+        source.decommit(2);
+        source.push_slice_lossy(&[5, 6]);
+        assert!(source.is_wrapped());
+        let mut destination: BipBuffer<u8> = BipBuffer::new(6);
+        destination.push_slice_lossy(&[10, 11, 12, 13]);
+        destination.decommit(2);
+        destination.push_slice_lossy(&[14]);
+        assert_eq!(destination.append(&mut source), 2);
+        assert_eq!(destination.to_vec(), vec![12, 13, 14, 3, 4]);
+        assert_eq!(source.to_vec(), vec![5, 6]);
+        assert_eq!(destination.append(&mut source), 0);
+        destination.decommit(4);
+        assert_eq!(destination.append(&mut source), 2);
+        assert_eq!(destination.to_vec(), vec![4, 5, 6]);
+        assert!(source.is_empty());
+    }
+    #[test]
+    fn swap_remove_wrapped() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(6);
+        buffer.push_slice_lossy(&[1, 2, 3, 4, 5, 6]);
+        buffer.decommit(3);
+        buffer.push_slice_lossy(&[7, 8]);
+        assert_eq!(buffer.swap_remove(5), None);
+        assert_eq!(buffer.swap_remove(1), Some(5));
+        assert_eq!(buffer.to_vec(), vec![4, 8, 6, 7]);
+        assert_eq!(buffer.swap_remove(3), Some(7));
+        assert_eq!(buffer.to_vec(), vec![4, 8, 6]);
+        assert!(!buffer.is_wrapped());
+        assert_eq!(buffer.swap_remove(0), Some(4));
+        assert_eq!(buffer.to_vec(), vec![6, 8]);
+    }
+    #[test]
+    fn reserve_aligned() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(16);
+        buffer.push_slice_lossy(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        buffer.reserve_aligned(4, 4).unwrap();
+        assert_eq!(buffer.reserved_region(), (8, 12));
+        buffer.commit(2);
+        assert_eq!(buffer.a_region(), (0, 10));
+        assert!(buffer.reserve_aligned(2, 4).is_err());
+        buffer.decommit(8);
+        buffer
+            .reserve_aligned(8, 4)
+            .unwrap()
+            .copy_from_slice(&[1; 8]);
+        assert_eq!(buffer.reserved_region(), (0, 8));
+        assert!(buffer.reserved_region().0.is_multiple_of(4));
+        buffer.commit(8);
+        assert!(buffer.is_wrapped());
+        assert_eq!(buffer.committed_len(), 10);
+        assert!(buffer.reserve_aligned(1, 3).is_err());
+        assert_eq!(buffer.reserve_aligned(0, 8).unwrap().len(), 0);
+        assert_eq!(buffer.reserved_region(), (8, 8));
+    }
+    #[test]
+    fn eq_ignores_layout() {
+        let mut wrapped: BipBuffer<u8> = BipBuffer::new(4);
+        wrapped.push_slice_lossy(&[0, 0, 1, 2]);
+        wrapped.decommit(2);
+        wrapped.push_slice_lossy(&[3]);
+        assert!(wrapped.is_wrapped());
+        let mut flat: BipBuffer<u8> = BipBuffer::new(8);
+        flat.push_slice_lossy(&[1, 2, 3]);
+        flat.reserve(2).unwrap();
+        assert_eq!(wrapped, flat);
+        flat.commit(0);
+        flat.push_slice_lossy(&[4]);
+        assert_ne!(wrapped, flat);
+    }
+    #[test]
+    fn hash_ignores_layout() {
+        use std::collections::hash_map::DefaultHasher;
+        fn hash_of(buffer: &BipBuffer<u8>) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            buffer.hash(&mut hasher);
+            hasher.finish()
+        }
+        let mut wrapped: BipBuffer<u8> = BipBuffer::new(4);
+        wrapped.push_slice_lossy(&[0, 0, 1, 2]);
+        wrapped.decommit(2);
+        wrapped.push_slice_lossy(&[3]);
+        assert!(wrapped.is_wrapped());
+        let mut flat: BipBuffer<u8> = BipBuffer::new(8);
+        flat.push_slice_lossy(&[1, 2, 3]);
+        flat.reserve(2).unwrap();
+        assert_eq!(hash_of(&wrapped), hash_of(&flat));
+    }
+    #[test]
+    fn compacted_free_len_fragmented() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(8);
+        buffer.push_slice_lossy(&[1, 2, 3, 4, 5]);
+        buffer.decommit(3);
+        assert!(buffer.is_fragmented());
+        assert_eq!(buffer.contiguous_free_len(), 3);
+        assert_eq!(buffer.compacted_free_len(), 6);
+        buffer.compact();
+        assert_eq!(buffer.contiguous_free_len(), 6);
+        assert_eq!(buffer.compacted_free_len(), 6);
+    }
+    #[test]
+    fn commit_without_reservation() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(8);
+        buffer.commit(4);
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.check_invariants(), Ok(()));
+        buffer.push_slice_lossy(&[1, 2, 3]);
+        buffer.commit(4);
+        assert_eq!(buffer.read(), Some(&mut [1u8, 2, 3][..]));
+        assert_eq!(buffer.check_invariants(), Ok(()));
+    }
+    #[test]
+    fn try_commit_without_reservation() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(8);
+        let err = buffer.try_commit(4).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::NoReservation);
+        assert!(buffer.try_commit(0).is_ok());
+        buffer.reserve(4).unwrap();
+        assert!(buffer.try_commit(2).is_ok());
+        assert_eq!(buffer.committed_len(), 2);
+    }
+    #[test]
+    fn decommit_keeping() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(8);
+        buffer.push_slice_lossy(&[1, 2, 3, 4, 5, 6]);
+        buffer.decommit_keeping(2, 3);
+        assert_eq!(buffer.to_vec(), vec![3, 4, 5, 6]);
+        buffer.decommit_keeping(4, 3);
+        assert_eq!(buffer.to_vec(), vec![4, 5, 6]);
+        buffer.push_slice_lossy(&[7, 8, 9]);
+        assert!(buffer.is_wrapped());
+        buffer.decommit_keeping(10, 2);
+        assert_eq!(buffer.to_vec(), vec![8, 9]);
+        buffer.decommit_keeping(1, 5);
+        assert_eq!(buffer.to_vec(), vec![8, 9]);
+    }
+    #[test]
+    fn consume_prefix() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(8);
+        buffer.push_slice_lossy(&[1, 2, 3, 4, 5]);
+        buffer.consume(|block| {
+            assert_eq!(block, &[1, 2, 3, 4, 5]);
+            2
+        });
+        assert_eq!(buffer.read(), Some(&mut [3u8, 4, 5][..]));
+        buffer.consume(|_| 10);
+        assert!(buffer.is_empty());
+        buffer.consume(|block| {
+            assert!(block.is_empty());
+            1
+        });
+        assert_eq!(buffer.check_invariants(), Ok(()));
+    }
+    #[test]
+    fn framed_round_trip() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(16);
+        assert_eq!(buffer.push_framed(&[1, 2, 3]).unwrap(), 7);
+        assert_eq!(buffer.push_framed(&[]).unwrap(), 4);
+        assert_eq!(buffer.committed_len(), 11);
+        assert_eq!(buffer.read_framed(), Some(vec![1, 2, 3]));
+        assert_eq!(buffer.read_framed(), Some(vec![]));
+        assert_eq!(buffer.read_framed(), None);
+        assert!(buffer.is_empty());
+    }
+    #[test]
+    fn framed_partial() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(8);
+        assert!(buffer.push_framed(&[1, 2, 3, 4, 5]).is_err());
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.reserved_len(), 0);
+        buffer.push_slice_lossy(&[0, 0, 0, 3, 1]);
+        assert_eq!(buffer.read_framed(), None);
+        assert_eq!(buffer.committed_len(), 5);
+        buffer.push_slice_lossy(&[2, 3]);
+        assert_eq!(buffer.read_framed(), Some(vec![1, 2, 3]));
+    }
+    #[test]
+    fn free_regions() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(10);
+        assert_eq!(buffer.free_regions(), vec![(0, 10)]);
+        buffer.push_slice_lossy(&[1, 2, 3, 4, 5, 6]);
+        buffer.decommit(3);
+        assert_eq!(buffer.free_regions(), vec![(0, 3), (6, 10)]);
+        buffer.reserve(2).unwrap();
+        assert_eq!(buffer.free_regions(), vec![(0, 3), (8, 10)]);
+        buffer.commit(2);
+        buffer.push_slice_lossy(&[7, 8, 9]);
+        assert!(buffer.is_wrapped());
+        assert_eq!(buffer.free_regions(), vec![(8, 10)]);
+        let free: usize = buffer
+            .free_regions()
+            .iter()
+            .map(|(start, end)| end - start)
+            .sum();
+        assert_eq!(free, buffer.free_len());
+    }
+    #[test]
+    fn debug_overlap_checks() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(8);
+        buffer.set_debug_overlap_checks(true);
+        buffer.push_slice_lossy(&[1, 2, 3, 4, 5, 6]);
+        buffer.decommit(4);
+        buffer.reserve(4).unwrap();
+        buffer.commit(2);
+        buffer.reserve(4).unwrap();
+        buffer.commit(2);
+        assert!(buffer.is_wrapped());
+        buffer.decommit(3);
+        buffer.reserve(8).unwrap();
+    }
+    #[test]
+    #[should_panic(expected = "reservation overlaps the A region")]
+    fn assert_reservation_disjoint() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(8);
+        buffer.push_slice_lossy(&[1, 2, 3, 4]);
+        buffer.regions.reserve_start = 2;
+        buffer.regions.reserve_end = 6;
+        buffer.regions.assert_reservation_disjoint();
+    }
+    #[test]
+    #[should_panic(expected = "reservation overlaps the B region")]
+    fn assert_reservation_disjoint_b() {
+        let regions = Regions {
+            a_start: 4,
+            a_end: 8,
+            b_end: 3,
+            reserve_start: 2,
+            reserve_end: 4,
+            ..Regions::default()
+        };
+        regions.assert_reservation_disjoint();
+    }
+    #[test]
+    #[should_panic(expected = "reservation overlaps the A region")]
+    fn assert_reservation_disjoint_wrapped_part() {
+        let regions = Regions {
+            a_start: 2,
+            a_end: 6,
+            reserve_start: 6,
+            reserve_end: 8,
+            reserve_wrap_end: 3,
+            ..Regions::default()
+        };
+        regions.assert_reservation_disjoint();
+    }
+    #[test]
+    fn assert_reservation_disjoint_consistent() {
+        let regions = Regions {
+            a_start: 2,
+            a_end: 6,
+            b_end: 1,
+            reserve_start: 1,
+            reserve_end: 2,
+            ..Regions::default()
+        };
+        regions.assert_reservation_disjoint();
+    }
+    #[test]
+    fn utilization() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(8);
+        assert_eq!(buffer.utilization(), 0.0);
+        buffer.push_slice_lossy(&[1, 2, 3, 4]);
+        assert_eq!(buffer.utilization(), 0.5);
+        let empty: BipBuffer<u8> = BipBuffer::new(0);
+        assert_eq!(empty.utilization(), 0.0);
+    }
+    #[test]
+    fn as_records() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(16);
+        buffer.push_slice_lossy(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+        let records: Vec<&[u8; 4]> = buffer.as_records::<4>().collect();
+        assert_eq!(records, vec![&[1, 2, 3, 4], &[5, 6, 7, 8]]);
+    }
+    #[test]
+    fn as_records_wrapped() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(12);
+        buffer.push_slice_lossy(&[9, 9, 9, 9, 9, 0]);
+        buffer.decommit(5);
+        buffer.push_slice_lossy(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]);
+        assert_eq!(buffer.committed_lens(), (7, 5));
+        let records: Vec<&[u8; 4]> = buffer.as_records::<4>().collect();
+        assert_eq!(records, vec![&[0, 1, 2, 3], &[8, 9, 10, 11]]);
+        buffer.compact();
+        assert_eq!(buffer.as_records::<4>().count(), 3);
+    }
+    #[test]
+    fn fill_from_prefix() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(8);
+        let written = buffer.fill_from(|block| {
+            assert_eq!(block.len(), 8);
+            block[..3].copy_from_slice(&[1, 2, 3]);
+            3
+        });
+        assert_eq!(written, 3);
+        assert_eq!(buffer.to_vec(), vec![1, 2, 3]);
+        assert_eq!(buffer.reserved_len(), 0);
+        assert_eq!(buffer.fill_from(|_| 10), 5);
+        assert_eq!(buffer.committed_len(), 8);
+        assert_eq!(buffer.fill_from(|_| panic!("called when full")), 0);
+    }
+    #[test]
+    fn rotate_left() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(6);
+        buffer.push_slice_lossy(&[0, 0, 0, 1, 2, 3]);
+        buffer.decommit(3);
+        buffer.push_slice_lossy(&[4, 5]);
+        assert!(buffer.is_wrapped());
+        buffer.rotate_left(2);
+        assert_eq!(
+            buffer.iter().copied().collect::<Vec<_>>(),
+            vec![3, 4, 5, 1, 2]
+        );
+        buffer.rotate_left(0);
+        assert_eq!(
+            buffer.iter().copied().collect::<Vec<_>>(),
+            vec![3, 4, 5, 1, 2]
+        );
+        buffer.rotate_left(6);
+        assert_eq!(
+            buffer.iter().copied().collect::<Vec<_>>(),
+            vec![4, 5, 1, 2, 3]
+        );
+        let mut empty: BipBuffer<u8> = BipBuffer::new(4);
+        empty.rotate_left(3);
+        assert!(empty.is_empty());
+    }
+    #[test]
+    fn max_committed() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(8);
+        buffer.set_max_committed(3);
+        buffer.push_slice_lossy(&[1, 2]);
+        assert_eq!(buffer.to_vec(), vec![1, 2]);
+        buffer.push_slice_lossy(&[3, 4, 5]);
+        assert_eq!(buffer.to_vec(), vec![3, 4, 5]);
+        buffer.push_slice_lossy(&[6, 7, 8, 9]);
+        assert_eq!(buffer.to_vec(), vec![7, 8, 9]);
+        buffer.set_max_committed(0);
+        buffer.push_slice_lossy(&[10, 11]);
+        assert_eq!(buffer.to_vec(), vec![7, 8, 9, 10, 11]);
+    }
+    #[test]
+    fn reserve_in_b() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(10);
+        assert!(buffer.reserve_in_b(1).is_err());
+        buffer.push_slice_lossy(&[1, 2, 3, 4, 5]);
+        buffer.decommit(3);
+        assert!(buffer.reserve_in_b(4).is_err());
+        buffer.reserve_in_b(2).unwrap().copy_from_slice(&[6, 7]);
+        assert_eq!(buffer.reserved_region(), (0, 2));
+        buffer.commit(2);
+        assert!(buffer.is_wrapped());
+        assert_eq!(buffer.b_region(), (0, 2));
+        buffer.reserve_in_b(1).unwrap()[0] = 8;
+        buffer.commit(1);
+        assert_eq!(buffer.to_vec(), vec![4, 5, 6, 7, 8]);
+        assert!(buffer.reserve_in_b(1).is_err());
+    }
+    #[test]
+    fn dropped_count() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(4);
+        buffer.push_slice_lossy(&[1, 2, 3]);
+        buffer.decommit(1);
+        assert_eq!(buffer.dropped_count(), 0);
+        buffer.push_slice_lossy(&[4, 5, 6]);
+        assert_eq!(buffer.to_vec(), vec![3, 4, 5, 6]);
+        assert_eq!(buffer.dropped_count(), 1);
+        buffer.push_slice_lossy(&[7, 8, 9, 10, 11, 12]);
+        assert_eq!(buffer.to_vec(), vec![9, 10, 11, 12]);
+        assert_eq!(buffer.dropped_count(), 7);
+        buffer.reset_dropped_count();
+        buffer.set_max_committed(2);
+        buffer.decommit(4);
+        buffer.push_slice_lossy(&[1, 2, 3]);
+        assert_eq!(buffer.dropped_count(), 1);
+    }
+    #[test]
+    fn from_ring_wrapped() {
+        let buffer = BipBuffer::from_ring(vec![4, 5, 0, 0, 1, 2, 3], 4, 5);
+        assert_eq!(buffer.len(), 7);
+        assert_eq!(buffer.to_vec(), vec![1, 2, 3, 4, 5]);
+        assert_eq!(buffer.committed_lens(), (5, 0));
+        assert_eq!(buffer.contiguous_free_len(), 2);
+    }
+    #[test]
+    fn from_ring_contiguous() {
+        let buffer = BipBuffer::from_ring(vec![0, 1, 2, 0], 1, 2);
+        assert_eq!(buffer.to_vec(), vec![1, 2]);
+        let empty: BipBuffer<u8> = BipBuffer::from_ring(Vec::new(), 0, 0);
+        assert!(empty.is_empty());
+    }
+    #[test]
+    fn peek_mut_n() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(6);
+        buffer.push_slice_lossy(&[0, 0, 0, 1, 2, 3]);
+        buffer.decommit(3);
+        buffer.peek_mut_n(2).unwrap().copy_from_slice(&[8, 9]);
+        assert_eq!(buffer.to_vec(), vec![8, 9, 3]);
+        assert_eq!(buffer.peek_mut_n(4), None);
+        buffer.push_slice_lossy(&[4]);
+        assert!(buffer.is_wrapped());
+        assert_eq!(buffer.peek_mut_n(3).map(|slice| slice.len()), Some(3));
+        assert_eq!(buffer.peek_mut_n(4), None);
+        assert_eq!(buffer.committed_len(), 4);
+    }
+    #[test]
+    fn ensure_contiguous() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(8);
+        buffer.push_slice_lossy(&[0, 0, 0, 0, 0, 1, 2, 3]);
+        buffer.decommit(5);
+        buffer.push_slice_lossy(&[4, 5, 6]);
+        assert_eq!(buffer.committed_lens(), (3, 3));
+        assert_eq!(buffer.ensure_contiguous(2), Some(&mut [1u8, 2][..]));
+        assert!(buffer.is_wrapped());
+        assert_eq!(
+            buffer.ensure_contiguous(5),
+            Some(&mut [1u8, 2, 3, 4, 5][..])
+        );
+        assert!(!buffer.is_wrapped());
+        assert_eq!(buffer.committed_len(), 6);
+        assert_eq!(buffer.ensure_contiguous(7), None);
+    }
+    #[test]
+    fn push_slice_lossy_drops_only_needed() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(8);
+        buffer.push_slice_lossy(&[0, 0, 0, 0, 1, 2]);
+        buffer.decommit(4);
+        assert_eq!(buffer.a_region(), (4, 6));
+        assert_eq!(buffer.push_slice_lossy(&[10, 11, 12, 13, 14, 15, 16]), 7);
+        assert_eq!(buffer.to_vec(), vec![2, 10, 11, 12, 13, 14, 15, 16]);
+        assert_eq!(buffer.dropped_count(), 1);
+    }
+    #[test]
+    fn generation_tracks_region_changes() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(6);
+        buffer.push_slice_lossy(&[1, 2, 3, 4, 5, 6]);
+        buffer.decommit(3);
+        buffer.push_slice_lossy(&[7, 8]);
+        let mut observed = buffer.generation();
+        buffer.compact();
+        assert!(buffer.generation() > observed);
+        observed = buffer.generation();
+        buffer.compact();
+        assert_eq!(buffer.generation(), observed);
+        buffer.truncate(4);
+        assert!(buffer.generation() > observed);
+        observed = buffer.generation();
+        buffer.retain(|&value| value != 5);
+        assert!(buffer.generation() > observed);
+        observed = buffer.generation();
+        buffer.retain(|_| true);
+        assert_eq!(buffer.generation(), observed);
+        buffer.swap_remove(0);
+        assert!(buffer.generation() > observed);
+        observed = buffer.generation();
+        buffer.clear();
+        assert!(buffer.generation() > observed);
+        observed = buffer.generation();
+        buffer.clear();
+        assert_eq!(buffer.generation(), observed);
+        buffer.reset(4);
+        assert!(buffer.generation() > observed);
+    }
+    #[test]
+    fn checkpoint_restore() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(8);
+        buffer.push_slice_lossy(&[1, 2, 3, 4]);
+        let checkpoint = buffer.checkpoint();
+        buffer.decommit(3);
+        buffer.decommit(1);
+        assert!(buffer.is_empty());
+        buffer.restore(checkpoint).unwrap();
+        assert_eq!(buffer.to_vec(), vec![1, 2, 3, 4]);
+        assert_eq!(buffer.check_invariants(), Ok(()));
+        buffer.decommit(2);
+        buffer.restore(checkpoint).unwrap();
+        assert_eq!(buffer.to_vec(), vec![1, 2, 3, 4]);
+    }
+    #[test]
+    fn checkpoint_stale() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(4);
+        buffer.push_slice_lossy(&[1, 2, 3, 4]);
+        let checkpoint = buffer.checkpoint();
+        buffer.decommit(4);
+        buffer.reserve(4).unwrap().copy_from_slice(&[5, 6, 7, 8]);
+        buffer.commit(4);
+        assert_eq!(buffer.a_region(), (0, 4));
+        let err = buffer.restore(checkpoint).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::StaleCheckpoint);
+        assert_eq!(buffer.to_vec(), vec![5, 6, 7, 8]);
+        let checkpoint = buffer.checkpoint();
+        buffer.decommit(2);
+        buffer.reserve(1).unwrap();
+        buffer.commit(0);
+        assert!(buffer.restore(checkpoint).is_err());
+        assert_eq!(buffer.to_vec(), vec![7, 8]);
+    }
+    #[test]
+    fn is_fragmented_b_touching_a() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(8);
+        buffer.push_slice_lossy(&[1, 2, 3, 4]);
+        buffer.decommit(2);
+        buffer.reserve_in_b(2).unwrap();
+        buffer.commit(2);
+        assert_eq!(buffer.b_region(), (0, 2));
+        assert_eq!(buffer.a_region(), (2, 4));
+        assert_eq!(buffer.free_len(), 4);
+        assert_eq!(buffer.contiguous_free_len(), 0);
+        assert!(buffer.is_fragmented());
+    }
+    #[test]
+    fn reserve_between_invalid() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(8);
+        assert!(buffer.reserve_between(6, 2).is_err());
+        assert_eq!(buffer.reserved_len(), 0);
+        buffer.reserve(3).unwrap();
+        buffer.commit(3);
+        buffer.reserve(2).unwrap();
+        assert!(buffer.reserve_between(6, 8).is_err());
+        assert_eq!(buffer.reserved_region(), (3, 5));
+        assert!(buffer.reserve_between(4, 2).is_err());
+        assert_eq!(buffer.reserved_region(), (3, 5));
+    }
+    #[test]
+    fn rotate_left_noop_keeps_reservation() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(6);
+        buffer.push_slice_lossy(&[0, 0, 1, 2, 3]);
+        buffer.decommit(2);
+        buffer.reserve(1).unwrap();
+        let generation = buffer.generation();
+        buffer.rotate_left(0);
+        buffer.rotate_left(3);
+        assert_eq!(buffer.a_region(), (2, 5));
+        assert_eq!(buffer.reserved_region(), (0, 1));
+        assert_eq!(buffer.generation(), generation);
+        assert_eq!(buffer.to_vec(), vec![1, 2, 3]);
+    }
+    #[test]
+    fn compacted_free_len_reserved() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(8);
+        buffer.push_slice_lossy(&[1, 2, 3]);
+        buffer.reserve(3).unwrap();
+        assert_eq!(buffer.compacted_free_len(), 2);
+        buffer.commit(0);
+        assert_eq!(buffer.compacted_free_len(), 5);
+        buffer.reserve(3).unwrap();
+        buffer.compact();
+        assert_eq!(buffer.reserved_len(), 0);
+        assert_eq!(buffer.compacted_free_len(), 5);
+    }
+    #[test]
+    fn reserve_vectored_before_a_only() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(6);
+        buffer.push_slice_lossy(&[1, 2, 3, 4, 5, 6]);
+        buffer.decommit(2);
+        {
+            let (first, second) = buffer.reserve_vectored(4);
+            assert_eq!(first.len(), 2);
+            assert!(second.is_empty());
+            first.copy_from_slice(&[7, 8]);
+        }
+        assert_eq!(buffer.reserved_region(), (0, 2));
+        buffer.commit(2);
+        assert_eq!(buffer.to_vec(), vec![3, 4, 5, 6, 7, 8]);
+    }
+    #[test]
+    fn drain_decommits_once() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(4);
+        buffer.push_slice_lossy(&[1, 2, 3, 4]);
+        let generation = buffer.generation();
+        assert_eq!(buffer.drain().take(3).collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(buffer.generation(), generation + 1);
+        assert_eq!(buffer.stats().decommitted, 3);
+        assert_eq!(buffer.to_vec(), vec![4]);
+        assert_eq!(Vec::from(buffer), vec![0, 0, 0, 4]);
+    }
+    #[test]
+    fn checkpoint_stale_after_drain() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(4);
+        buffer.push_slice_lossy(&[1, 2, 3, 4]);
+        let checkpoint = buffer.checkpoint();
+        assert_eq!(buffer.drain().next(), Some(1));
+        let err = buffer.restore(checkpoint).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::StaleCheckpoint);
+        assert_eq!(buffer.to_vec(), vec![2, 3, 4]);
+        let checkpoint = buffer.checkpoint();
+        buffer.drain().for_each(drop);
+        assert!(buffer.restore(checkpoint).is_err());
+        assert!(buffer.is_empty());
+        let checkpoint = buffer.checkpoint();
+        assert_eq!(buffer.drain().next(), None);
+        assert!(buffer.restore(checkpoint).is_ok());
+    }
+    #[test]
+    fn generation_ignores_noops() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(4);
+        buffer.decommit(2);
+        buffer.commit(2);
+        assert_eq!(buffer.generation(), 0);
+        buffer.push_slice_lossy(&[1, 2]);
+        assert_eq!(buffer.generation(), 1);
+        buffer.reserve(2).unwrap();
+        buffer.commit(0);
+        buffer.decommit(0);
+        buffer.truncate(4);
+        assert_eq!(buffer.generation(), 1);
+        buffer.commit(1);
+        buffer.decommit(1);
+        assert_eq!(buffer.generation(), 2);
+    }
+    #[test]
+    fn framed_max_committed() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(16);
+        buffer.set_max_committed(6);
+        assert_eq!(buffer.push_framed(&[1, 2]).unwrap(), 6);
+        assert!(buffer.push_framed(&[3, 4]).is_err());
+        assert_eq!(buffer.committed_len(), 6);
+        assert_eq!(buffer.read_framed(), Some(vec![1, 2]));
+        assert_eq!(buffer.push_framed(&[3, 4]).unwrap(), 6);
+        assert_eq!(buffer.read_framed(), Some(vec![3, 4]));
+    }
+    #[test]
+    fn read_from_overreporting() {
+        struct Liar;
+        impl Read for Liar {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                buf.fill(7);
+                Ok(100)
+            }
+        }
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(4);
+        assert_eq!(buffer.read_from(&mut Liar).unwrap(), 4);
+        assert_eq!(buffer.committed_len(), 4);
+        assert_eq!(buffer.read_from(&mut Liar).unwrap(), 0);
+    }
+    #[test]
+    fn with_default_without_default_impl() {
+        #[derive(Clone, Debug, PartialEq)]
+        struct Sentinel(u8);
+        let buffer = BipBuffer::with_default(3, Sentinel(7));
+        assert_eq!(Vec::from(buffer), vec![Sentinel(7); 3]);
+    }
+    #[test]
+    fn from_ring_empty_head_at_end() {
+        let buffer = BipBuffer::from_ring(vec![1, 2, 3], 3, 0);
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.len(), 3);
+        #[derive(Debug, PartialEq)]
+        struct NoDefault(u8);
+        let ring = BipBuffer::from_ring(vec![NoDefault(2), NoDefault(1)], 1, 2);
+        assert_eq!(Vec::from(ring), vec![NoDefault(1), NoDefault(2)]);
+    }
+    #[test]
+    fn reserve_aligned_unaligned_a() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(16);
+        buffer.push_slice_lossy(&[1, 2, 3, 4, 5]);
+        assert!(buffer.reserve_aligned(4, 4).is_err());
+        assert_eq!(buffer.stats().no_space_errors, 1);
+        buffer.set_auto_grow(true);
+        buffer.set_debug_overlap_checks(true);
+        buffer.decommit(5);
+        buffer.push_slice_lossy(&[1; 12]);
+        buffer.reserve_aligned(8, 4).unwrap();
+        assert_eq!(buffer.len(), 32);
+        assert_eq!(buffer.reserved_region(), (12, 20));
+    }
+    #[test]
+    #[should_panic(expected = "alignment must be nonzero")]
+    fn reserve_aligned_zero() {
+        let mut buffer: BipBuffer<u8> = BipBuffer::new(4);
+        let _ = buffer.reserve_aligned(1, 0);
+    }
+    #[test]
+    fn append_full_keeps_stats() {
+        let mut source: BipBuffer<u8> = BipBuffer::new(4);
+        source.push_slice_lossy(&[1, 2, 3]);
+        let mut dest: BipBuffer<u8> = BipBuffer::new(2);
+        assert_eq!(dest.append(&mut source), 2);
+        assert_eq!(dest.append(&mut source), 0);
+        assert_eq!(dest.stats().no_space_errors, 0);
+        assert_eq!(source.to_vec(), vec![3]);
+    }
 }
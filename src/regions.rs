@@ -0,0 +1,396 @@
+//! Region bookkeeping shared by the Bip-Buffer implementations
+
+use crate::error::{Error, ErrorKind};
+use std::ops::Range;
+
+/// Boundaries of the `A`, `B`, and reserved regions within a backing store
+///
+/// Operations that touch element data take the backing store as a slice, so the same logic
+/// works for any kind of storage.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Regions {
+    /// Index of the start of the `A` region
+    pub(crate) a_start: usize,
+    /// Index of the end of the `A` region
+    pub(crate) a_end: usize,
+    /// Index of the start of the `B` region
+    pub(crate) b_start: usize,
+    /// Index of the end of the `B` region
+    pub(crate) b_end: usize,
+    /// Index of the start of the reserved region
+    pub(crate) reserve_start: usize,
+    /// Index of the end of the reserved region
+    pub(crate) reserve_end: usize,
+    /// Index of the start of the wrapped part of a vectored reservation
+    pub(crate) reserve_wrap_start: usize,
+    /// Index of the end of the wrapped part of a vectored reservation
+    pub(crate) reserve_wrap_end: usize,
+}
+
+impl Regions {
+    /// Clears all regions and reservations
+    pub(crate) fn clear(&mut self) {
+        *self = Regions::default();
+    }
+
+    /// Reserves up to `length` slots of `buffer`
+    ///
+    /// Returns a `NoSpace` error if there is no free space
+    pub(crate) fn reserve<'a, T>(
+        &mut self,
+        buffer: &'a mut [T],
+        length: usize,
+    ) -> Result<&'a mut [T], Error> {
+        if self.free_block(buffer.len()).1 == 0 {
+            return Err(ErrorKind::NoSpace.into());
+        }
+        Ok(self.reserve_up_to(buffer, length))
+    }
+
+    /// Reserves up to `length` slots of `buffer`, which may be none at all
+    pub(crate) fn reserve_up_to<'a, T>(
+        &mut self,
+        buffer: &'a mut [T],
+        length: usize,
+    ) -> &'a mut [T] {
+        let (reserve_start, free_space) = self.free_block(buffer.len());
+        let reserve_length = std::cmp::min(free_space, length);
+        self.clear_reservation();
+        self.reserve_start = reserve_start;
+        self.reserve_end = reserve_start + reserve_length;
+        self.debug_check_invariants(buffer.len());
+        &mut buffer[self.reserve_start..self.reserve_end]
+    }
+
+    /// Reserves exactly `length` slots of `buffer`, starting at an index that is a multiple of
+    /// `align`
+    ///
+    /// A reservation can only start where committing it would keep its region contiguous, so
+    /// the aligned candidates are the end of the region the next commit would extend, and the
+    /// start of the backing store. Returns a `NoSpace` error if neither has room. Panics if
+    /// `align` is zero.
+    pub(crate) fn reserve_aligned<'a, T>(
+        &mut self,
+        buffer: &'a mut [T],
+        length: usize,
+        align: usize,
+    ) -> Result<&'a mut [T], Error> {
+        assert!(align > 0, "alignment must be nonzero");
+        let len = buffer.len();
+        let candidate = if self.committed_len() == 0 {
+            Some((0, len))
+        } else if self.is_wrapped() {
+            Some((self.b_end, self.a_start)).filter(|(start, _)| start.is_multiple_of(align))
+        } else if self.a_end.is_multiple_of(align) && len - self.a_end >= length {
+            Some((self.a_end, len))
+        } else {
+            Some((0, self.a_start))
+        };
+        match candidate {
+            Some((start, end)) if end - start >= length => {
+                self.clear_reservation();
+                self.reserve_start = start;
+                self.reserve_end = start + length;
+                self.debug_check_invariants(len);
+                Ok(&mut buffer[self.reserve_start..self.reserve_end])
+            }
+            _ => Err(ErrorKind::NoSpace.into()),
+        }
+    }
+
+    /// Reserves exactly `length` slots of `buffer` from the gap before the `A` region, so that
+    /// committing them adds to the `B` region
+    ///
+    /// Returns a `NoSpace` error if the `A` region is empty or the gap is too small
+    pub(crate) fn reserve_in_b<'a, T>(
+        &mut self,
+        buffer: &'a mut [T],
+        length: usize,
+    ) -> Result<&'a mut [T], Error> {
+        let start = if self.is_wrapped() { self.b_end } else { 0 };
+        if self.a_start == self.a_end || self.a_start - start < length {
+            return Err(ErrorKind::NoSpace.into());
+        }
+        self.clear_reservation();
+        self.reserve_start = start;
+        self.reserve_end = start + length;
+        self.debug_check_invariants(buffer.len());
+        Ok(&mut buffer[self.reserve_start..self.reserve_end])
+    }
+
+    /// Reserves up to `length` slots of `buffer`, split across the free space after `A` and
+    /// the free space before it when both are available
+    ///
+    /// If only one block is free, it is always returned as the first slice
+    pub(crate) fn reserve_vectored<'a, T>(
+        &mut self,
+        buffer: &'a mut [T],
+        length: usize,
+    ) -> (&'a mut [T], &'a mut [T]) {
+        if self.is_wrapped() || self.committed_len() == 0 || self.a_end == buffer.len() {
+            return (self.reserve_up_to(buffer, length), Default::default());
+        }
+        let after_a = std::cmp::min(buffer.len() - self.a_end, length);
+        let before_a = std::cmp::min(self.a_start, length - after_a);
+        self.clear_reservation();
+        self.reserve_start = self.a_end;
+        self.reserve_end = self.a_end + after_a;
+        if before_a > 0 {
+            self.reserve_wrap_end = before_a;
+        }
+        self.debug_check_invariants(buffer.len());
+        let (front, back) = buffer.split_at_mut(self.a_end);
+        (&mut back[..after_a], &mut front[..before_a])
+    }
+
+    /// Finds the free block the next reservation will be placed in, given a backing store of
+    /// `len` elements
+    ///
+    /// Returns the start index and length of the block
+    pub(crate) fn free_block(&self, len: usize) -> (usize, usize) {
+        if self.is_wrapped() {
+            (self.b_end, self.a_start - self.b_end)
+        } else {
+            let space_after_a = len - self.a_end;
+            if space_after_a >= self.a_start {
+                (self.a_end, space_after_a)
+            } else {
+                (0, self.a_start)
+            }
+        }
+    }
+
+    /// Commits up to `length` elements of the reservation, then clears it
+    ///
+    /// The wrapped part of a vectored reservation is only used once the first part is full.
+    /// Does nothing if there is no active reservation.
+    pub(crate) fn commit(&mut self, length: usize) {
+        if self.reserved_len() == 0 {
+            self.clear_reservation();
+            return;
+        }
+        let first = std::cmp::min(length, self.reserve_end - self.reserve_start);
+        let wrapped = std::cmp::min(
+            length - first,
+            self.reserve_wrap_end - self.reserve_wrap_start,
+        );
+        if length > 0 {
+            self.commit_range(self.reserve_start, first);
+        }
+        if wrapped > 0 {
+            self.commit_range(self.reserve_wrap_start, wrapped);
+        }
+        self.clear_reservation();
+    }
+
+    /// Adds the `count` elements starting at `start` to whichever region they follow
+    fn commit_range(&mut self, start: usize, count: usize) {
+        if self.committed_len() == 0 {
+            // Nothing else is committed, so the range becomes `A` wherever it was placed
+            self.a_start = start;
+            self.a_end = start + count;
+            self.b_start = 0;
+            self.b_end = 0;
+        } else if !self.is_wrapped() && start == self.a_end {
+            // Directly after `A`, so it extends `A`
+            self.a_end += count;
+        } else {
+            // Wrapped around before `A`, so it starts or extends `B`
+            debug_assert!(
+                start + count <= self.a_start,
+                "reservation overlaps A region"
+            );
+            if !self.is_wrapped() {
+                self.b_start = start;
+                self.b_end = start;
+            }
+            self.b_end += count;
+        }
+    }
+
+    /// Clears both parts of the reservation
+    fn clear_reservation(&mut self) {
+        self.reserve_start = 0;
+        self.reserve_end = 0;
+        self.reserve_wrap_start = 0;
+        self.reserve_wrap_end = 0;
+    }
+
+    /// Marks the first `length` committed elements as seen, spilling over from `A` into `B`
+    pub(crate) fn decommit(&mut self, length: usize) {
+        let a_len = self.a_end - self.a_start;
+        if length < a_len {
+            self.a_start += length;
+        } else {
+            let remaining = length - a_len;
+            if remaining < self.b_end - self.b_start {
+                self.a_start = self.b_start + remaining;
+                self.a_end = self.b_end;
+            } else {
+                self.a_start = 0;
+                self.a_end = 0;
+            }
+            self.b_start = 0;
+            self.b_end = 0;
+        }
+    }
+
+    /// Drops the most recently committed elements until only `length` remain, trimming `B`
+    /// before `A`, and clears any reservation
+    pub(crate) fn truncate(&mut self, length: usize) {
+        if length >= self.committed_len() {
+            return;
+        }
+        let a_len = self.a_end - self.a_start;
+        if length > a_len {
+            self.b_end = self.b_start + (length - a_len);
+        } else {
+            self.b_start = 0;
+            self.b_end = 0;
+            if length == 0 {
+                self.a_start = 0;
+                self.a_end = 0;
+            } else {
+                self.a_end = self.a_start + length;
+            }
+        }
+        self.clear_reservation();
+    }
+
+    /// Moves all committed data in `buffer`, in logical order, to a single `A` region at the
+    /// start, clearing any reservation
+    pub(crate) fn compact<T>(&mut self, buffer: &mut [T]) {
+        let a_len = self.a_end - self.a_start;
+        let b_len = self.b_end - self.b_start;
+        if b_len > 0 {
+            // Close the gap between B and A, then swap them into logical order
+            buffer[self.b_end..self.a_end].rotate_left(self.a_start - self.b_end);
+            buffer[self.b_start..self.b_end + a_len].rotate_left(b_len);
+            buffer[..self.b_end + a_len].rotate_left(self.b_start);
+        } else {
+            buffer[..self.a_end].rotate_left(self.a_start);
+        }
+        *self = Regions {
+            a_end: a_len + b_len,
+            ..Regions::default()
+        };
+    }
+
+    /// The `A` region as a range of indices
+    #[inline]
+    pub(crate) fn a(&self) -> Range<usize> {
+        self.a_start..self.a_end
+    }
+
+    /// The `B` region as a range of indices
+    #[inline]
+    pub(crate) fn b(&self) -> Range<usize> {
+        self.b_start..self.b_end
+    }
+
+    /// The reserved region as a range of indices
+    #[inline]
+    pub(crate) fn reserved(&self) -> Range<usize> {
+        self.reserve_start..self.reserve_end
+    }
+
+    /// Number of committed elements
+    #[inline]
+    pub(crate) fn committed_len(&self) -> usize {
+        self.a_end - self.a_start + self.b_end - self.b_start
+    }
+
+    /// Number of reserved elements, including the wrapped part of a vectored reservation
+    #[inline]
+    pub(crate) fn reserved_len(&self) -> usize {
+        self.reserve_end - self.reserve_start + self.reserve_wrap_end - self.reserve_wrap_start
+    }
+
+    /// Whether committed data is split across the `A` and `B` regions
+    #[inline]
+    pub(crate) fn is_wrapped(&self) -> bool {
+        self.b_end > self.b_start
+    }
+
+    /// Verifies the relationships between region indices, given a backing store of `len`
+    /// elements
+    ///
+    /// Returns a description of the first violation found
+    pub(crate) fn check_invariants(&self, len: usize) -> Result<(), &'static str> {
+        if self.a_start > self.a_end {
+            return Err("A region starts after it ends");
+        }
+        if self.a_end > len {
+            return Err("A region exceeds backing store");
+        }
+        if self.b_start > self.b_end {
+            return Err("B region starts after it ends");
+        }
+        if self.b_end > len {
+            return Err("B region exceeds backing store");
+        }
+        if self.is_wrapped() {
+            if self.a_start == self.a_end {
+                return Err("B region is committed without an A region");
+            }
+            if self.b_end > self.a_start {
+                return Err("B region overlaps A region");
+            }
+        }
+        if self.reserve_start > self.reserve_end {
+            return Err("reservation starts after it ends");
+        }
+        if self.reserve_end > len {
+            return Err("reservation exceeds backing store");
+        }
+        if self.reserve_wrap_start > self.reserve_wrap_end {
+            return Err("wrapped reservation starts after it ends");
+        }
+        if self.reserve_wrap_end > len {
+            return Err("wrapped reservation exceeds backing store");
+        }
+        let reserved = self.reserved();
+        let reserved_wrap = self.reserve_wrap_start..self.reserve_wrap_end;
+        if overlaps(&reserved, &self.a()) || overlaps(&reserved_wrap, &self.a()) {
+            return Err("reservation overlaps A region");
+        }
+        if overlaps(&reserved, &self.b()) || overlaps(&reserved_wrap, &self.b()) {
+            return Err("reservation overlaps B region");
+        }
+        if overlaps(&reserved, &reserved_wrap) {
+            return Err("wrapped reservation overlaps reservation");
+        }
+        Ok(())
+    }
+
+    /// Verifies the relationships between region indices in debug builds, given a backing store
+    /// of `len` elements
+    #[inline]
+    pub(crate) fn debug_check_invariants(&self, len: usize) {
+        if cfg!(debug_assertions) {
+            if let Err(violation) = self.check_invariants(len) {
+                panic!("bip-buffer invariant violated: {}", violation);
+            }
+        }
+    }
+
+    /// Panics if the reservation overlaps either committed region
+    pub(crate) fn assert_reservation_disjoint(&self) {
+        for reserved in &[
+            self.reserved(),
+            self.reserve_wrap_start..self.reserve_wrap_end,
+        ] {
+            if overlaps(reserved, &self.a()) {
+                panic!("bip-buffer reservation overlaps the A region: {:?}", self);
+            }
+            if overlaps(reserved, &self.b()) {
+                panic!("bip-buffer reservation overlaps the B region: {:?}", self);
+            }
+        }
+    }
+}
+
+/// Whether two non-empty ranges share any indices
+fn overlaps(left: &Range<usize>, right: &Range<usize>) -> bool {
+    !left.is_empty() && !right.is_empty() && left.start < right.end && right.start < left.end
+}
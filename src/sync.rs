@@ -0,0 +1,159 @@
+//! A thread-safe wrapper around the Bip-Buffer
+
+use crate::BipBuffer;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// A `BipBuffer` shared between threads behind a `Mutex`
+///
+/// Cloning a `SyncBipBuffer` returns another handle to the same buffer, so a producer and a
+/// consumer can each hold one. Because reserved and committed slices can't outlive the lock,
+/// data is copied in and out rather than borrowed.
+///
+/// # Panics
+/// All methods panic if another thread panicked while holding the lock.
+#[derive(Debug)]
+pub struct SyncBipBuffer<T> {
+    inner: Arc<Mutex<BipBuffer<T>>>,
+}
+
+impl<T: Default> SyncBipBuffer<T> {
+    /// Creates and allocates a new shared buffer of `T` elements
+    pub fn new(length: usize) -> SyncBipBuffer<T> {
+        BipBuffer::new(length).into()
+    }
+
+    /// Copies as much of `data` as fits into the buffer and commits it
+    ///
+    /// Returns the number of elements stored, which may be less than `data.len()`
+    pub fn push_slice(&self, data: &[T]) -> usize
+    where
+        T: Copy,
+    {
+        let mut buffer = self.lock();
+        let mut pushed = 0;
+        while pushed < data.len() {
+            let reserved = match buffer.reserve(data.len() - pushed) {
+                Ok(reserved) => reserved,
+                Err(_) => break,
+            };
+            let count = reserved.len();
+            reserved.copy_from_slice(&data[pushed..pushed + count]);
+            buffer.commit(count);
+            pushed += count;
+        }
+        pushed
+    }
+
+    /// Copies committed data into `out` and decommits it
+    ///
+    /// Returns the number of elements copied, which may be less than `out.len()`
+    pub fn pop_slice(&self, out: &mut [T]) -> usize
+    where
+        T: Copy,
+    {
+        let mut buffer = self.lock();
+        let mut popped = 0;
+        while popped < out.len() {
+            let block = match buffer.read() {
+                Some(block) => block,
+                None => break,
+            };
+            let count = std::cmp::min(block.len(), out.len() - popped);
+            out[popped..popped + count].copy_from_slice(&block[..count]);
+            buffer.decommit(count);
+            popped += count;
+        }
+        popped
+    }
+
+    /// Number of committed elements
+    pub fn committed_len(&self) -> usize {
+        self.lock().committed_len()
+    }
+
+    /// Size of the backing store
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Whether any space has been reserved or committed in the buffer
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BipBuffer<T>> {
+        self.inner.lock().expect("bip-buffer lock poisoned")
+    }
+}
+
+impl<T> Clone for SyncBipBuffer<T> {
+    fn clone(&self) -> SyncBipBuffer<T> {
+        SyncBipBuffer {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<T> From<BipBuffer<T>> for SyncBipBuffer<T> {
+    fn from(buffer: BipBuffer<T>) -> SyncBipBuffer<T> {
+        SyncBipBuffer {
+            inner: Arc::new(Mutex::new(buffer)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn push_and_pop() {
+        let buffer: SyncBipBuffer<u8> = SyncBipBuffer::new(4);
+        assert_eq!(buffer.push_slice(&[1, 2, 3]), 3);
+        assert_eq!(buffer.push_slice(&[4, 5]), 1);
+        let mut out = [0u8; 2];
+        assert_eq!(buffer.pop_slice(&mut out), 2);
+        assert_eq!(out, [1, 2]);
+        assert_eq!(buffer.push_slice(&[5, 6, 7]), 2);
+        let mut out = [0u8; 8];
+        assert_eq!(buffer.pop_slice(&mut out), 4);
+        assert_eq!(out[..4], [3, 4, 5, 6]);
+        assert!(buffer.is_empty());
+    }
+    #[test]
+    fn threaded_producer_consumer() {
+        const TOTAL: u32 = 10_000;
+        let buffer: SyncBipBuffer<u32> = SyncBipBuffer::new(64);
+        let producer = {
+            let buffer = buffer.clone();
+            thread::spawn(move || {
+                let data: Vec<u32> = (0..TOTAL).collect();
+                let mut sent = 0;
+                while sent < data.len() {
+                    let end = std::cmp::min(sent + 37, data.len());
+                    sent += buffer.push_slice(&data[sent..end]);
+                    thread::yield_now();
+                }
+            })
+        };
+        let consumer = {
+            let buffer = buffer.clone();
+            thread::spawn(move || {
+                let mut received = Vec::with_capacity(TOTAL as usize);
+                let mut out = [0u32; 23];
+                while received.len() < TOTAL as usize {
+                    let count = buffer.pop_slice(&mut out);
+                    received.extend_from_slice(&out[..count]);
+                    thread::yield_now();
+                }
+                received
+            })
+        };
+        producer.join().unwrap();
+        let received = consumer.join().unwrap();
+        assert_eq!(received.len(), TOTAL as usize);
+        assert!(received.iter().copied().eq(0..TOTAL));
+        assert_eq!(buffer.committed_len(), 0);
+    }
+}